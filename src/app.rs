@@ -1,28 +1,63 @@
 use crate::analyzer::Analyser;
 use crate::analyzer::Analysis;
 use crate::analyzer::VariableRef;
+use crate::breakpoint::breakpoint_key;
+use crate::breakpoint::Breakpoints;
 use crate::config::Config;
+use crate::dbgp::client::Breakpoint;
+use crate::dbgp::client::BreakpointState;
+use crate::dbgp::client::BreakpointType;
 use crate::dbgp::client::ContextGetResponse;
+use crate::dbgp::client::ContextName;
+use crate::dbgp::client::ContinuationException;
 use crate::dbgp::client::ContinuationResponse;
 use crate::dbgp::client::ContinuationStatus;
 use crate::dbgp::client::DbgpClient;
+use crate::dbgp::client::DbgpError;
+use crate::dbgp::client::EngineNotify;
 use crate::dbgp::client::EvalResponse;
+use crate::dbgp::client::OutputMode;
+use crate::dbgp::client::Properties;
 use crate::dbgp::client::Property;
+use crate::dbgp::client::Stream;
+use crate::dbgp::proxy::proxy_init;
+use crate::dbgp::proxy::proxy_stop;
 use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Keymap;
+use crate::keymap::Scope;
 use crate::notification::Notification;
+use crate::state::State;
+use crate::step_filter::StepFilters;
 use crate::theme::Scheme;
 use crate::theme::Theme;
+use crate::view::breakpoints::breakpoint_label;
+use crate::view::breakpoints::BreakpointDialog;
+use crate::view::breakpoints::BreakpointDialogState;
+use crate::view::context::context_rows;
+use crate::view::context::selected_property;
+use crate::view::context::PropertyDialog;
+use crate::view::context::PropertyDialogState;
 use crate::view::eval::EvalDialog;
 use crate::view::help::HelpView;
 use crate::view::layout::LayoutView;
 use crate::view::listen::ListenView;
+use crate::view::properties::PropertyChanges;
 use crate::view::session::SessionView;
 use crate::view::session::SessionViewMode;
 use crate::view::session::SessionViewState;
+use crate::view::sessions::SessionsView;
+use crate::view::step_until::StepUntil;
+use crate::view::step_until::StepUntilDialog;
+use crate::view::timeline::timeline;
+use crate::view::timeline::TimelineState;
+use crate::view::timeline::TimelineView;
+use crate::view::watches::WatchDialog;
+use crate::view::watches::WatchDialogState;
 use crate::view::View;
+use crate::workspace::PathMappings;
 use crate::workspace::Workspace;
 use anyhow::Result;
-use crossterm::event::KeyCode;
 use log::error;
 use log::info;
 use log::warn;
@@ -34,26 +69,39 @@ use ratatui::widgets::Block;
 use ratatui::widgets::Padding;
 use ratatui::widgets::Paragraph;
 use ratatui::Terminal;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::io;
 use std::ops::DerefMut;
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
 use tokio::task;
+use tokio::time;
 use tui_input::Input;
 
 type AnalyzedFiles = HashMap<String, Analysis>;
 
+/// how long to wait for the engine to confirm an interrupt
+const INTERRUPT_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[derive(Clone, Debug)]
 pub struct StackFrame {
     pub level: u16,
     pub source: SourceContext,
-    pub context: Option<ContextGetResponse>,
+    /// the function or method of the frame as reported by the engine
+    pub function: Option<String>,
+    /// fetched contexts keyed by context ID
+    pub contexts: HashMap<u32, ContextGetResponse>,
 }
 #[derive(Clone,Debug)]
 pub struct Variable {
@@ -79,11 +127,19 @@ impl DocumentVariables {
     }
 }
 impl StackFrame {
+    /// the frames are of the same function, although not necessarily the
+    /// same call
+    pub(crate) fn is_same_function(&self, other: &StackFrame) -> bool {
+        self.source.filename == other.source.filename && self.function == other.function
+    }
+
+    pub(crate) fn context(&self, context_id: u32) -> Option<&ContextGetResponse> {
+        self.contexts.get(&context_id)
+    }
+
+    /// get a property from the locals context
     pub(crate) fn get_property(&self, name: &str) -> Option<&Property> {
-        match &self.context {
-            Some(c) => c.properties.get(name),
-            None => None,
-        }
+        self.context(0).and_then(|c| c.properties.get(name))
     }
 }
 
@@ -91,6 +147,8 @@ impl StackFrame {
 pub struct HistoryEntry {
     pub stacks: Vec<StackFrame>,
     pub eval: Option<EvalEntry>,
+    /// watch expressions evaluated at this step
+    pub watches: Vec<EvalEntry>,
 }
 
 #[derive(Clone, Debug)]
@@ -105,7 +163,11 @@ impl HistoryEntry {
     }
     fn new() -> Self {
         let stacks = Vec::new();
-        HistoryEntry { stacks, eval: None }
+        HistoryEntry {
+            stacks,
+            eval: None,
+            watches: vec![],
+        }
     }
 
     fn initial(filename: String, source: String) -> HistoryEntry {
@@ -117,9 +179,11 @@ impl HistoryEntry {
                     filename,
                     line_no: 0,
                 },
-                context: None,
+                function: None,
+                contexts: HashMap::new(),
             }],
             eval: None,
+            watches: vec![],
         }
     }
 
@@ -136,15 +200,21 @@ impl HistoryEntry {
     }
 }
 
+/// changes since the previous step keyed by offset, stack depth and context
+type ChangesCache = RefCell<HashMap<(usize, u16, u32), Rc<PropertyChanges>>>;
+
 pub struct History {
     pub entries: Vec<HistoryEntry>,
     pub offset: usize,
+    /// the changes are drawn on every tick but only change with the step
+    changes: ChangesCache,
 }
 impl History {
-    fn default() -> History {
+    pub(crate) fn default() -> History {
         Self {
             entries: vec![],
             offset: 0,
+            changes: RefCell::new(HashMap::new()),
         }
     }
 
@@ -177,7 +247,44 @@ impl History {
         self.entries.get(self.offset)
     }
 
+    /// the frame of the previous step which corresponds to the frame at the
+    /// given depth of the current step
+    pub(crate) fn previous_stack(&self, stack_depth: u16) -> Option<&StackFrame> {
+        let current = self.current()?;
+        let previous = self.entries.get(self.offset.checked_sub(1)?)?;
+        let frame = current.stack(stack_depth)?;
+        // frames are matched from the bottom of the stack as calls and
+        // returns only change the top
+        let from_bottom = current.stacks.len() - stack_depth as usize;
+        let previous_frame = previous
+            .stacks
+            .get(previous.stacks.len().checked_sub(from_bottom)?)?;
+        match previous_frame.is_same_function(frame) {
+            true => Some(previous_frame),
+            false => None,
+        }
+    }
+
+    /// changes to the properties of a context of the frame at the given depth
+    /// since the previous step
+    pub(crate) fn changes(&self, stack_depth: u16, context_id: u32) -> Option<Rc<PropertyChanges>> {
+        let key = (self.offset, stack_depth, context_id);
+        if let Some(changes) = self.changes.borrow().get(&key) {
+            return Some(Rc::clone(changes));
+        }
+        let current = self.current()?.stack(stack_depth)?.context(context_id)?;
+        let previous = self.previous_stack(stack_depth)?.context(context_id)?;
+        let changes = Rc::new(PropertyChanges::diff(
+            current.properties.defined_properties(),
+            previous.properties.defined_properties(),
+        ));
+        self.changes.borrow_mut().insert(key, Rc::clone(&changes));
+        Some(changes)
+    }
+
     pub(crate) fn current_mut(&mut self) -> Option<&mut HistoryEntry> {
+        // the properties of the step may be changed
+        self.changes.get_mut().clear();
         self.entries.get_mut(self.offset)
     }
 
@@ -187,6 +294,33 @@ impl History {
     }
 }
 
+/// progress of stepping until an expression is true
+pub struct StepUntilProgress {
+    pub expression: String,
+    pub steps: usize,
+    pub max_steps: usize,
+    /// set to stop stepping after the current step
+    pub cancel: Arc<AtomicBool>,
+}
+
+/// why stepping until an expression is true stopped
+#[derive(Debug)]
+pub enum StepUntilOutcome {
+    True(usize),
+    MaxSteps(usize),
+    Interrupted(usize),
+    Ended(usize),
+    Error(String),
+}
+
+/// output of the script and the index of the history entry of the step
+/// which produced it
+#[derive(Clone, Debug)]
+pub struct OutputChunk {
+    pub step: usize,
+    pub stream: Stream,
+}
+
 #[derive(Clone, Debug)]
 pub struct SourceContext {
     pub source: String,
@@ -207,24 +341,122 @@ impl SourceContext {
 pub enum SelectedView {
     Listen,
     Session,
+    Sessions,
     Help,
+    Timeline,
 }
 
 #[derive(Debug, Clone)]
 pub enum ActiveDialog {
     Eval,
+    Breakpoint,
+    Property,
+    Watch,
+    StepUntil,
 }
 
-#[derive(PartialEq)]
-pub enum ListenStatus {
+pub type SessionId = u32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SessionStatus {
     Connected,
-    Listening,
-    Refusing,
+    Disconnected,
+}
+
+/// a connection from the debugging engine, e.g. for a single HTTP request,
+/// queue worker or CLI command
+pub struct Session {
+    pub id: SessionId,
+    pub idekey: Option<String>,
+    pub fileuri: String,
+    pub status: SessionStatus,
+    pub client: Arc<Mutex<DbgpClient>>,
+    pub workspace: Workspace,
+    pub history: History,
+    pub document_variables: DocumentVariables,
+    pub analyzed_files: AnalyzedFiles,
+    pub server_status: Option<ContinuationStatus>,
+    pub server_exception: Option<ContinuationException>,
+    pub snapshot_notify: Arc<Notify>,
+    /// engine IDs of our breakpoints while this is not the current session
+    pub breakpoint_ids: HashMap<String, String>,
+    pub output: Vec<OutputChunk>,
+    /// set while a continuation command is in flight
+    pub running_since: Option<Instant>,
+    /// contexts offered by the engine, e.g. locals and superglobals
+    pub context_names: Vec<ContextName>,
+    pub step_until: Option<StepUntilProgress>,
+    /// the breakpoint set by "run to cursor" until the run stops
+    pub temporary_breakpoint: Option<Breakpoint>,
 }
 
-impl ListenStatus {
+impl Session {
+    fn new(id: SessionId, path_mappings: PathMappings) -> Session {
+        let client = Arc::new(Mutex::new(DbgpClient::new(None)));
+        Session {
+            id,
+            idekey: None,
+            fileuri: String::new(),
+            status: SessionStatus::Connected,
+            workspace: Workspace::new(Arc::clone(&client), path_mappings),
+            client,
+            history: History::default(),
+            document_variables: DocumentVariables::default(),
+            analyzed_files: HashMap::new(),
+            server_status: None,
+            server_exception: None,
+            snapshot_notify: Arc::new(Notify::new()),
+            breakpoint_ids: HashMap::new(),
+            output: vec![],
+            running_since: None,
+            context_names: vec![ContextName::locals()],
+            step_until: None,
+            temporary_breakpoint: None,
+        }
+    }
+
+    /// remove the breakpoint set by "run to cursor" unless it was hit, in
+    /// which case the engine has already removed it
+    async fn clear_temporary_breakpoint(&mut self) -> Result<()> {
+        let breakpoint = match self.temporary_breakpoint.take() {
+            Some(breakpoint) => breakpoint,
+            None => return Ok(()),
+        };
+        let hit = self
+            .history
+            .entries
+            .last()
+            .and_then(|entry| entry.stack(0))
+            .is_some_and(|frame| {
+                breakpoint.filename.as_ref() == Some(&frame.source.filename)
+                    && breakpoint.line_no == Some(frame.source.line_no)
+            });
+        match (&breakpoint.id, hit) {
+            (Some(id), false) => self.client.lock().await.breakpoint_remove(id).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// attribute output received from the engine to the latest step and
+    /// return the notifications received alongside it
+    async fn collect_output(&mut self) -> Vec<EngineNotify> {
+        let step = self.history.len().saturating_sub(1);
+        let mut client = self.client.lock().await;
+        self.output
+            .extend(client.take_output().into_iter().map(|stream| OutputChunk { step, stream }));
+        client.take_notifications()
+    }
+
     pub fn is_connected(&self) -> bool {
-        *self == ListenStatus::Connected
+        self.status == SessionStatus::Connected
+    }
+
+    /// the IDE key or, if not set, the session number
+    pub fn label(&self) -> String {
+        match &self.idekey {
+            Some(idekey) => idekey.clone(),
+            None => format!("#{}", self.id),
+        }
     }
 }
 
@@ -234,18 +466,21 @@ pub struct App {
     quit: bool,
     sender: Sender<AppEvent>,
 
-    pub listening_status: ListenStatus,
     pub notification: Notification,
     pub config: Config,
 
-    pub server_status: Option<ContinuationStatus>,
     pub command_input: Input,
     pub command_response: Option<String>,
-    pub client: Arc<Mutex<DbgpClient>>,
-    pub workspace: Workspace,
 
-    pub history: History,
-    pub document_variables: DocumentVariables,
+    pub sessions: Vec<Session>,
+    pub current_session: Option<usize>,
+    pub sessions_scroll: (u16, u16),
+    next_session_id: SessionId,
+
+    pub path_mappings: PathMappings,
+    pub breakpoints: Breakpoints,
+    /// watch expressions, evaluated at every step
+    pub watches: Vec<String>,
 
     pub view_current: SelectedView,
     pub focus_view: bool,
@@ -255,48 +490,46 @@ pub struct App {
 
     pub counter: u16,
 
-    pub snapshot_notify: Arc<Notify>,
     pub context_depth: u16,
     pub theme: Theme,
 
-    pub analyzed_files: AnalyzedFiles,
-
     pub stack_max_context_fetch: u16,
 }
 
 impl App {
     pub fn new(config: Config, receiver: Receiver<AppEvent>, sender: Sender<AppEvent>) -> App {
-        let client = Arc::new(Mutex::new(DbgpClient::new(None)));
+        let path_mappings = PathMappings::new(config.path_mappings.clone());
+        let mut session_view = SessionViewState::new();
+        if let Some(panes) = &config.panes {
+            session_view.panes = panes.clone();
+        }
         App {
             tick: 0,
-            listening_status: ListenStatus::Listening,
+            context_depth: config.context_depth,
+            stack_max_context_fetch: config.stack_max_context_fetch,
+            theme: config.theme.clone(),
             config,
             input_plurality: vec![],
             notification: Notification::none(),
             receiver,
             sender: sender.clone(),
             quit: false,
-            history: History::default(),
-            document_variables: DocumentVariables::default(),
-            client: Arc::clone(&client),
-            workspace: Workspace::new(Arc::clone(&client)),
+            sessions: vec![],
+            current_session: None,
+            sessions_scroll: (0, 0),
+            next_session_id: 1,
+            path_mappings,
+            breakpoints: Breakpoints::default(),
+            watches: vec![],
 
             counter: 0,
-            context_depth: 4,
-            stack_max_context_fetch: 1,
 
-            theme: Theme::SolarizedDark,
-            server_status: None,
             command_input: Input::default(),
             command_response: None,
             view_current: SelectedView::Listen,
             active_dialog: None,
             focus_view: false,
-            session_view: SessionViewState::new(),
-
-            snapshot_notify: Arc::new(Notify::new()),
-
-            analyzed_files: HashMap::new(),
+            session_view,
         }
     }
 
@@ -323,6 +556,8 @@ impl App {
                 }
             };
 
+            // connections forwarded by a DBGp proxy are handled in the same
+            // way as direct connections from the engine
             loop {
                 match listener.accept().await {
                     Ok(s) => match sender.send(AppEvent::ClientConnected(s.0)).await {
@@ -335,6 +570,12 @@ impl App {
         });
 
         self.notification = Notification::info("Welcome to debug-tui press ? for help".to_string());
+        if let Err(e) = self.load_state() {
+            self.notification = Notification::error(e.to_string());
+        }
+        if let Err(e) = self.proxy_init().await {
+            self.notification = Notification::error(format!("Could not register with proxy: {}", e));
+        }
 
         loop {
             let event = self.receiver.recv().await;
@@ -352,6 +593,14 @@ impl App {
             };
 
             if self.quit {
+                if let Err(e) = self.save_state() {
+                    error!("Could not save state: {}", e);
+                }
+                if let (Some(proxy), Some(idekey)) = (&self.config.proxy, &self.config.idekey) {
+                    if let Err(e) = proxy_stop(proxy, idekey).await {
+                        error!("Could not unregister from proxy: {}", e);
+                    }
+                }
                 return Ok(());
             }
 
@@ -408,11 +657,14 @@ impl App {
             }
             AppEvent::HistoryNext => {
                 for _ in 0..self.take_motion() {
-                    self.history.next();
+                    let session = match self.session_mut() {
+                        Some(session) => session,
+                        None => break,
+                    };
+                    session.history.next();
+                    let back_to_current = session.history.is_current() && session.is_connected();
                     self.recenter();
-                    if self.history.is_current()
-                        && (self.listening_status == ListenStatus::Connected)
-                    {
+                    if back_to_current {
                         self.sender
                             .send(AppEvent::ChangeSessionViewMode(SessionViewMode::Current))
                             .await?;
@@ -421,46 +673,148 @@ impl App {
             }
             AppEvent::HistoryPrevious => {
                 for _ in 0..self.take_motion() {
-                    self.history.previous();
+                    if let Some(session) = self.session_mut() {
+                        session.history.previous();
+                    }
                     self.recenter();
                 }
             }
             AppEvent::Listen => {
-                self.listening_status = ListenStatus::Listening;
                 self.view_current = SelectedView::Listen;
                 self.session_view.mode = SessionViewMode::Current;
                 self.notification = Notification::info("listening for next connection".to_string())
             }
             AppEvent::ClientConnected(s) => {
-                if self.listening_status != ListenStatus::Listening {
-                    self.notification =
-                        Notification::warning("refused incoming connection".to_string());
-                } else {
-                    self.notification = Notification::info("connected".to_string());
-                    let filepath = {
-                        let mut client = self.client.lock().await;
-                        let response = client.deref_mut().connect(s).await?;
-                        for (feature, value) in [
-                            ("max_depth", self.context_depth.to_string().as_str()),
-                            ("extended_properties", "1"),
-                        ] {
-                            info!("setting feature {} to {:?}", feature, value);
-                            client.feature_set(feature, value).await?;
+                let mut session = Session::new(self.next_session_id, self.path_mappings.clone());
+                self.next_session_id += 1;
+                let init = {
+                    let mut client = session.client.lock().await;
+                    let init = client.deref_mut().connect(s).await?;
+                    for (feature, value) in [
+                        ("max_depth", self.context_depth.to_string().as_str()),
+                        ("extended_properties", "1"),
+                        ("notify_ok", "1"),
+                    ] {
+                        info!("setting feature {} to {:?}", feature, value);
+                        client.feature_set(feature, value).await?;
+                    }
+                    if self.config.output != OutputMode::Disable {
+                        client.stdout(self.config.output).await?;
+                        // not all engines support redirecting stderr
+                        if let Err(e) = client.stderr(self.config.output).await {
+                            warn!("could not redirect stderr: {}", e);
                         }
-                        response.fileuri.clone()
-                    };
-                    self.listening_status = ListenStatus::Connected;
-                    self.reset();
+                    }
+                    match client.context_names(0).await {
+                        Ok(names) if !names.is_empty() => session.context_names = names,
+                        Ok(_) => (),
+                        Err(e) => warn!("could not get context names: {}", e),
+                    }
+                    session.breakpoint_ids =
+                        set_breakpoints(&mut client, &self.breakpoints, &self.path_mappings).await?;
+                    init
+                };
+                session.idekey = init.idekey;
+                session.fileuri = init.fileuri.clone();
+
+                let source = session.workspace.open(init.fileuri.clone()).await?;
+                session
+                    .history
+                    .push(HistoryEntry::initial(init.fileuri, source.text.clone()));
+
+                let label = session.label();
+                self.sessions.push(session);
 
-                    let source = self.workspace.open(filepath.clone()).await;
-                    self.history = History::default();
-                    self.history
-                        .push(HistoryEntry::initial(filepath.clone(), source.text.clone()));
+                // only switch to the new session if we are not debugging another
+                let switch = match (&self.view_current, self.session()) {
+                    (SelectedView::Listen, _) | (_, None) => true,
+                    (_, Some(current)) => !current.is_connected(),
+                };
+                if switch {
+                    self.switch_session(self.sessions.len() - 1).await?;
+                    self.notification = Notification::info(format!("connected {}", label));
+                } else {
+                    self.notification = Notification::info(format!(
+                        "new session {}, press [{}] to list sessions",
+                        label,
+                        self.keymap().keys(Action::Sessions)
+                    ));
                 }
             }
-            AppEvent::Snapshot() => {
-                self.snapshot().await?;
-                self.snapshot_notify.notify_one();
+            AppEvent::Snapshot(id) => {
+                self.snapshot(id).await?;
+                if let Some(session) = self.session_by_id(id) {
+                    session.snapshot_notify.notify_one();
+                }
+            }
+            AppEvent::ScrollSessions(amount) => {
+                let scroll = apply_scroll(self.sessions_scroll, amount, self.take_motion() as i16);
+                self.sessions_scroll = (
+                    scroll.0.min(self.sessions.len().saturating_sub(1) as u16),
+                    scroll.1,
+                );
+            }
+            AppEvent::Timeline(fullname) => {
+                let session = match self.session() {
+                    Some(session) => session,
+                    None => return Ok(()),
+                };
+                let state = session.history.current().and_then(|entry| {
+                    TimelineState::new(
+                        fullname,
+                        self.session_view.context_id,
+                        entry,
+                        self.session_view.stack_depth(),
+                    )
+                });
+                if let Some(mut state) = state {
+                    // start on the row of the current step
+                    let row = timeline(&session.history, &state)
+                        .iter()
+                        .rposition(|row| row.step <= session.history.offset);
+                    state.scroll = (row.unwrap_or(0) as u16, 0);
+                    self.session_view.timeline = state;
+                    self.view_current = SelectedView::Timeline;
+                }
+            }
+            AppEvent::ScrollTimeline(amount) => {
+                let len = self.session().map_or(0, |session| {
+                    timeline(&session.history, &self.session_view.timeline).len()
+                });
+                let scroll = apply_scroll(self.session_view.timeline.scroll, amount, self.take_motion() as i16);
+                self.session_view.timeline.scroll = (scroll.0.min(len.saturating_sub(1) as u16), scroll.1);
+            }
+            AppEvent::TimelineJump => {
+                let state = &self.session_view.timeline;
+                let session = match self.current_session.and_then(|index| self.sessions.get_mut(index)) {
+                    Some(session) => session,
+                    None => return Ok(()),
+                };
+                let row = timeline(&session.history, state)
+                    .get(state.scroll.0 as usize)
+                    .map(|row| (row.step, row.stack_depth));
+                if let Some((step, stack_depth)) = row {
+                    session.history.offset = step;
+                    self.session_view.mode = match session.history.is_current() && session.is_connected() {
+                        true => SessionViewMode::Current,
+                        false => SessionViewMode::History,
+                    };
+                    self.session_view.stack_scroll = (stack_depth, 0);
+                    self.recenter();
+                }
+                self.view_current = SelectedView::Session;
+            }
+            AppEvent::SwitchSession => {
+                let index = self.sessions_scroll.0 as usize;
+                if index < self.sessions.len() {
+                    self.switch_session(index).await?;
+                }
+            }
+            AppEvent::CloseSession => {
+                let index = self.sessions_scroll.0 as usize;
+                if index < self.sessions.len() {
+                    self.close_session(index).await;
+                }
             }
             AppEvent::StepOut => {
                 self.exec_continuation(AppEvent::StepOut).await;
@@ -474,14 +828,53 @@ impl App {
             AppEvent::Run => {
                 self.exec_continuation(AppEvent::Run).await;
             }
+            AppEvent::Stop => self.end_session(false).await?,
+            AppEvent::Detach => self.end_session(true).await?,
+            AppEvent::Interrupt => {
+                match self.session() {
+                    Some(Session {
+                        step_until: Some(progress),
+                        ..
+                    }) => {
+                        progress.cancel.store(true, Ordering::Relaxed);
+                        self.notification = Notification::info("interrupting".to_string());
+                    }
+                    Some(session) if session.running_since.is_some() => {
+                        let pending = session.client.lock().await.break_execution().await?;
+                        let sender = self.sender.clone();
+                        // the engine may not answer until the script reaches
+                        // a statement, don't block the event loop meanwhile
+                        task::spawn(async move {
+                            let result = match time::timeout(INTERRUPT_TIMEOUT, pending.broken()).await {
+                                Ok(result) => result.map_err(|e| e.to_string()),
+                                Err(_) => Err("Engine did not respond to break".to_string()),
+                            };
+                            let _ = sender.send(AppEvent::Interrupted(result)).await;
+                        });
+                        self.notification = Notification::info("interrupting".to_string());
+                    }
+                    _ => {
+                        self.notification = Notification::warning("Script is not running".to_string());
+                    }
+                }
+            }
+            AppEvent::Interrupted(result) => {
+                self.notification = match result {
+                    Ok(()) => Notification::info("interrupted".to_string()),
+                    Err(e) => Notification::error(e),
+                };
+            }
             AppEvent::ContextDepth(inc) => {
                 let depth = self.context_depth;
                 self.context_depth = depth.wrapping_add(inc as u16).clamp(1, 9);
-                self.client
-                    .lock()
-                    .await
-                    .feature_set("max_depth", self.context_depth.to_string().as_str())
-                    .await?;
+                for session in self.sessions.iter().filter(|s| s.is_connected()) {
+                    session
+                        .client
+                        .lock()
+                        .await
+                        .feature_set("max_depth", self.context_depth.to_string().as_str())
+                        .await?;
+                }
             }
             AppEvent::ContextFilterOpen => {
                 self.session_view.context_filter.show = true;
@@ -492,18 +885,179 @@ impl App {
                 self.focus_view = false;
             }
             AppEvent::ScrollSource(amount) => {
-                self.session_view.source_scroll = apply_scroll(
-                    self.session_view.source_scroll,
+                let motion = self.take_motion() as i16;
+                let line_count = self
+                    .source()
+                    .map_or(0, |source| source.source.lines().count());
+                self.session_view
+                    .move_source_cursor(amount.0.saturating_mul(motion), line_count as u32);
+                self.session_view.source_scroll =
+                    apply_scroll(self.session_view.source_scroll, (0, amount.1), motion);
+            }
+            AppEvent::RunToCursor => self.run_to_cursor().await?,
+            AppEvent::StepUntilStart => {
+                if !self.session().is_some_and(|s| s.is_connected() && s.history.is_current()) {
+                    self.notification =
+                        Notification::warning("Cannot step in history mode".to_string());
+                } else if !self.warn_if_running() {
+                    // keep the previous values
+                    self.session_view.step_until_dialog.focus = 0;
+                    self.active_dialog = Some(ActiveDialog::StepUntil);
+                }
+            }
+            AppEvent::StepUntilDialogCancel => {
+                self.active_dialog = None;
+            }
+            AppEvent::StepUntilDialogApply => {
+                let step_until = self.session_view.step_until_dialog.step_until()?;
+                self.active_dialog = None;
+                self.step_until(step_until);
+            }
+            AppEvent::StepUntilProgress(id, steps) => {
+                if let Some(progress) = self.session_by_id_mut(id).and_then(|s| s.step_until.as_mut()) {
+                    progress.steps = steps;
+                }
+            }
+            AppEvent::StepUntilDone(id, outcome) => {
+                let session = match self.session_by_id_mut(id) {
+                    Some(session) => session,
+                    None => return Ok(()),
+                };
+                session.running_since = None;
+                let expression = match session.step_until.take() {
+                    Some(progress) => progress.expression,
+                    None => return Ok(()),
+                };
+                self.notification = match outcome {
+                    StepUntilOutcome::True(steps) => {
+                        Notification::info(format!("{} is true after {} steps", expression, steps))
+                    }
+                    StepUntilOutcome::MaxSteps(steps) => Notification::warning(format!(
+                        "{} is still false after {} steps",
+                        expression, steps
+                    )),
+                    StepUntilOutcome::Interrupted(steps) => {
+                        Notification::info(format!("Interrupted after {} steps", steps))
+                    }
+                    StepUntilOutcome::Ended(steps) => Notification::warning(format!(
+                        "Script ended after {} steps before {} was true",
+                        steps, expression
+                    )),
+                    StepUntilOutcome::Error(message) => {
+                        Notification::error(format!("Could not evaluate {}: {}", expression, message))
+                    }
+                };
+            }
+            AppEvent::ToggleBreakpoint => {
+                if let Some(source) = self.source() {
+                    self.toggle_breakpoint(source.filename, self.session_view.source_cursor)
+                        .await?;
+                }
+            }
+            AppEvent::ScrollBreakpoints(amount) => {
+                let scroll = apply_scroll(
+                    self.session_view.breakpoints_scroll,
                     amount,
                     self.take_motion() as i16,
                 );
+                self.session_view.breakpoints_scroll = (
+                    scroll
+                        .0
+                        .min(self.breakpoints.breakpoints.len().saturating_sub(1) as u16),
+                    scroll.1,
+                );
+            }
+            AppEvent::BreakpointToggleState => {
+                let client = self.connected_client();
+                if let Some(breakpoint) = self
+                    .breakpoints
+                    .breakpoints
+                    .get_mut(self.session_view.breakpoints_scroll.0 as usize)
+                {
+                    breakpoint.state = match breakpoint.state {
+                        BreakpointState::Enabled => BreakpointState::Disabled,
+                        BreakpointState::Disabled => BreakpointState::Enabled,
+                    };
+                    if let (Some(id), Some(client)) = (&breakpoint.id, client) {
+                        client
+                            .lock()
+                            .await
+                            .breakpoint_update(id, &breakpoint.state)
+                            .await?;
+                    }
+                }
+            }
+            AppEvent::BreakpointDelete => {
+                let index = self.session_view.breakpoints_scroll.0 as usize;
+                if index < self.breakpoints.breakpoints.len() {
+                    let breakpoint = self.breakpoints.breakpoints.remove(index);
+                    if let (Some(id), Some(client)) = (&breakpoint.id, self.connected_client()) {
+                        client.lock().await.breakpoint_remove(id).await?;
+                    }
+                    self.session_view.breakpoints_scroll.0 = self
+                        .session_view
+                        .breakpoints_scroll
+                        .0
+                        .min(self.breakpoints.breakpoints.len().saturating_sub(1) as u16);
+                    self.notification = Notification::info(format!(
+                        "Removed breakpoint {}",
+                        breakpoint_label(&breakpoint)
+                    ));
+                }
+            }
+            AppEvent::BreakpointJump => {
+                let breakpoint = self
+                    .breakpoints
+                    .breakpoints
+                    .get(self.session_view.breakpoints_scroll.0 as usize);
+                if let Some(breakpoint) = breakpoint {
+                    match (breakpoint.filename.clone(), breakpoint.line_no) {
+                        (Some(filename), Some(line_no)) => {
+                            let filename = self.path_mappings.remote_uri(&filename);
+                            self.jump_to_source(filename, line_no).await?;
+                        }
+                        _ => {
+                            self.notification = Notification::warning(
+                                "Breakpoint does not have a source location".to_string(),
+                            );
+                        }
+                    }
+                }
             }
             AppEvent::ScrollContext(amount) => {
-                self.session_view.context_scroll = apply_scroll(
+                let scroll = apply_scroll(
                     self.session_view.context_scroll,
                     amount,
                     self.take_motion() as i16,
                 );
+                self.session_view.context_scroll = (
+                    scroll.0.min(context_rows(self).len().saturating_sub(1) as u16),
+                    scroll.1,
+                );
+            }
+            AppEvent::ContextSwitch => {
+                if let Some(session) = self.session() {
+                    let names = &session.context_names;
+                    let index = names
+                        .iter()
+                        .position(|n| n.id == self.session_view.context_id)
+                        .map_or(0, |index| (index + 1) % names.len());
+                    self.session_view.context_id = names[index].id;
+                    self.session_view.context_scroll = (0, 0);
+                    self.populate_stack_context().await?;
+                }
+            }
+            AppEvent::ContextFetchChildren(fullname) => {
+                self.fetch_children(&fullname).await?;
+                self.session_view.context_collapsed.remove(&fullname);
+            }
+            AppEvent::ScrollOutput(amount) => {
+                // output is scrolled up from the latest line of the current step
+                self.session_view.output_scroll = apply_scroll(
+                    self.session_view.output_scroll,
+                    (-amount.0, amount.1),
+                    self.take_motion() as i16,
+                );
             }
             AppEvent::ScrollEval(amount) => {
                 self.session_view.eval_state.scroll = apply_scroll(
@@ -524,24 +1078,30 @@ impl App {
             AppEvent::ToggleFullscreen => {
                 self.session_view.full_screen = !self.session_view.full_screen;
             }
-            AppEvent::UpdateStatus(server_status) => {
-                if let ContinuationStatus::Stopping = server_status {
-                    self.sender.send(AppEvent::Disconnect).await.unwrap();
+            AppEvent::UpdateStatus(id, response) => self.update_status(id, response).await?,
+            AppEvent::Disconnect(id) => {
+                let is_current = self.session().is_some_and(|s| s.id == id);
+                if let Some(session) = self.session_by_id_mut(id) {
+                    let notifications = session.collect_output().await;
+                    let _ = session.client.lock().await.deref_mut().disonnect().await;
+                    session.status = SessionStatus::Disconnected;
+                    session.running_since = None;
+                    self.notify(notifications, is_current);
+                    if is_current {
+                        self.sender
+                            .send(AppEvent::ChangeSessionViewMode(SessionViewMode::History))
+                            .await?;
+                    }
                 }
-                self.server_status = Some(server_status);
-            }
-            AppEvent::Disconnect => {
-                let _ = self.client.lock().await.deref_mut().disonnect().await;
-                self.listening_status = ListenStatus::Refusing;
-                self.sender
-                    .send(AppEvent::ChangeSessionViewMode(SessionViewMode::History))
-                    .await?;
             }
             AppEvent::PushInputPlurality(char) => self.input_plurality.push(char),
             AppEvent::EvalStart => {
-                if !self.history.is_current() {
+                if !self.session().is_some_and(|s| s.history.is_current()) {
                     self.notification =
                         Notification::warning("Cannot eval in history mode".to_string());
+                } else if self.session().is_some_and(|s| s.running_since.is_some()) {
+                    self.notification =
+                        Notification::warning("Cannot eval while the script is running".to_string());
                 } else {
                     self.active_dialog = Some(ActiveDialog::Eval);
                 }
@@ -552,8 +1112,9 @@ impl App {
             AppEvent::EvalExecute => {
                 if self.session_view.eval_state.input.to_string().is_empty() {
                     self.session_view.eval_state.response = None;
-                } else {
-                    let response = self
+                } else if let Some(session) = self.session() {
+                    let id = session.id;
+                    let response = session
                         .client
                         .lock()
                         .await
@@ -564,10 +1125,96 @@ impl App {
                         .await?;
 
                     self.session_view.eval_state.response = Some(response);
-                    self.sender.send(AppEvent::Snapshot()).await.unwrap();
+                    self.sender.send(AppEvent::Snapshot(id)).await.unwrap();
+                }
+                self.active_dialog = None;
+            }
+            AppEvent::BreakpointEdit => {
+                if let Some(source) = self.source() {
+                    let filename = self.path_mappings.local_uri(&source.filename);
+                    let line_no = self.session_view.source_cursor;
+                    if line_no > 0 {
+                        let mut breakpoint = match self.breakpoints.at_line(&filename, line_no) {
+                            Some(breakpoint) => breakpoint.clone(),
+                            None => Breakpoint::line(filename, line_no),
+                        };
+                        if let (Some(id), Some(client)) = (&breakpoint.id, self.connected_client()) {
+                            let engine_breakpoint = client.lock().await.breakpoint_get(id).await?;
+                            breakpoint.hit_count = engine_breakpoint.hit_count;
+                        }
+                        self.session_view.breakpoint_dialog = BreakpointDialogState::edit(breakpoint);
+                        self.active_dialog = Some(ActiveDialog::Breakpoint);
+                    }
+                }
+            }
+            AppEvent::BreakpointAdd(breakpoint_type) => {
+                let breakpoint = Breakpoint {
+                    exception: match breakpoint_type {
+                        BreakpointType::Exception => Some("*".to_string()),
+                        _ => None,
+                    },
+                    breakpoint_type,
+                    ..Default::default()
+                };
+                self.session_view.breakpoint_dialog = BreakpointDialogState::edit(breakpoint);
+                self.active_dialog = Some(ActiveDialog::Breakpoint);
+            }
+            AppEvent::BreakpointDialogCancel => {
+                self.active_dialog = None;
+            }
+            AppEvent::BreakpointDialogApply => {
+                let breakpoint = self.session_view.breakpoint_dialog.breakpoint()?;
+                self.active_dialog = None;
+                self.replace_breakpoint(breakpoint).await?;
+            }
+            AppEvent::PropertyEdit => {
+                if !self.session().is_some_and(|s| s.is_connected() && s.history.is_current()) {
+                    self.notification = Notification::warning(
+                        "Values can only be set in the current step".to_string(),
+                    );
+                } else if self.session().is_some_and(|s| s.running_since.is_some()) {
+                    self.notification = Notification::warning(
+                        "Cannot set values while the script is running".to_string(),
+                    );
+                } else if let Some(property) = selected_property(self) {
+                    match PropertyDialogState::edit(property) {
+                        Ok(state) => {
+                            self.session_view.property_dialog = state;
+                            self.active_dialog = Some(ActiveDialog::Property);
+                        }
+                        Err(e) => self.notification = Notification::warning(e.to_string()),
+                    }
                 }
+            }
+            AppEvent::PropertyDialogCancel => {
+                self.active_dialog = None;
+            }
+            AppEvent::PropertyDialogApply => {
+                let value = self.session_view.property_dialog.value()?;
+                self.active_dialog = None;
+                self.set_property(&value).await?;
+            }
+            AppEvent::ScrollWatches(amount) => {
+                let scroll = apply_scroll(
+                    self.session_view.watches_scroll,
+                    amount,
+                    self.take_motion() as i16,
+                );
+                self.session_view.watches_scroll = (
+                    scroll.0.min(self.watches.len().saturating_sub(1) as u16),
+                    scroll.1,
+                );
+            }
+            AppEvent::WatchAdd => {
+                self.session_view.watch_dialog = WatchDialogState::default();
+                self.active_dialog = Some(ActiveDialog::Watch);
+            }
+            AppEvent::WatchEdit => self.edit_watch(),
+            AppEvent::WatchDelete => self.delete_watch(),
+            AppEvent::WatchDialogCancel => {
                 self.active_dialog = None;
             }
+            AppEvent::WatchDialogApply => self.apply_watch_dialog().await?,
             AppEvent::Input(key_event) => {
                 if self.active_dialog.is_some() {
                     self.send_event_to_current_dialog(event).await;
@@ -576,18 +1223,22 @@ impl App {
                     self.send_event_to_current_view(event).await;
                 } else {
                     // global events
-                    match key_event.code {
-                        KeyCode::Char('t') => {
+                    match self.keymap().action(Scope::Global, &key_event) {
+                        Some(Action::ThemeNext) => {
                             self.theme = self.theme.next();
                             self.notification =
                                 Notification::info(format!("Switched to theme: {:?}", self.theme));
                         }
-                        KeyCode::Char('?') => {
+                        Some(Action::Help) => {
                             self.sender
                                 .send(AppEvent::ChangeView(SelectedView::Help))
                                 .await
                                 .unwrap();
                         }
+                        Some(Action::Sessions) => {
+                            self.sessions_scroll.0 = self.current_session.unwrap_or(0) as u16;
+                            self.view_current = SelectedView::Sessions;
+                        }
                         _ => self.send_event_to_current_view(event).await,
                     }
                 }
@@ -598,14 +1249,210 @@ impl App {
         Ok(())
     }
 
+    /// stop the script or detach from it, in both cases the engine closes
+    /// the connection
+    async fn end_session(&mut self, detach: bool) -> Result<()> {
+        let session = match self.session() {
+            Some(session) if session.is_connected() => session,
+            _ => return Ok(()),
+        };
+        if session.running_since.is_some() {
+            self.notification = Notification::warning(format!(
+                "Script is running, press [{}] to interrupt it first",
+                self.keymap().keys(Action::Interrupt)
+            ));
+            return Ok(());
+        }
+        let id = session.id;
+        let label = session.label();
+        let response = {
+            let mut client = session.client.lock().await;
+            match detach {
+                true => client.detach().await,
+                false => client.stop().await,
+            }
+        };
+        self.notification = match (&response, detach) {
+            (Ok(response), true) => Notification::info(format!(
+                "{} detached ({}), the script continues without the debugger",
+                label, response.status
+            )),
+            (Ok(response), false) => {
+                Notification::info(format!("{} script stopped ({})", label, response.status))
+            }
+            (Err(e), _) => Notification::error(format!(
+                "Could not {} {}: {}",
+                if detach { "detach from" } else { "stop" },
+                label,
+                e
+            )),
+        };
+        if let (Ok(response), Some(session)) = (response, self.session_mut()) {
+            session.server_status = Some(response.status);
+        }
+        self.sender.send(AppEvent::Disconnect(id)).await?;
+        Ok(())
+    }
+
+    /// warn that a continuation cannot be started while the script is running
+    fn warn_if_running(&mut self) -> bool {
+        if self.session().is_some_and(|s| s.running_since.is_some()) {
+            self.notification = Notification::warning(format!(
+                "Script is running, press [{}] to interrupt",
+                self.keymap().keys(Action::Interrupt)
+            ));
+            return true;
+        }
+        false
+    }
+
+    /// run until the cursor line of the source pane is reached, using a
+    /// breakpoint which the engine removes once it has been hit
+    async fn run_to_cursor(&mut self) -> Result<()> {
+        let line_no = self.session_view.source_cursor;
+        let source = match self.source() {
+            Some(source) if line_no > 0 => source,
+            _ => return Ok(()),
+        };
+        if !self.session().is_some_and(|s| s.history.is_current()) {
+            self.notification =
+                Notification::warning("Cannot run to the cursor in history mode".to_string());
+            return Ok(());
+        }
+        let client = match self.connected_client() {
+            Some(client) => client,
+            None => return Ok(()),
+        };
+        if self.warn_if_running() {
+            return Ok(());
+        }
+        let mut breakpoint = Breakpoint::line(self.path_mappings.local_uri(&source.filename), line_no);
+        breakpoint.temporary = true;
+        let mut breakpoint = self.path_mappings.remote_breakpoint(&breakpoint);
+        breakpoint.id = Some(client.lock().await.breakpoint_set(&breakpoint).await?.id);
+        if let Some(session) = self.session_mut() {
+            session.temporary_breakpoint = Some(breakpoint);
+        }
+        self.exec_continuation(AppEvent::Run).await;
+        Ok(())
+    }
+
+    /// step until the expression is true, only the final step is recorded in
+    /// the history
+    fn step_until(&mut self, step_until: StepUntil) {
+        if self.warn_if_running() {
+            return;
+        }
+        let sender = self.sender.clone();
+        let session = match self.session_mut() {
+            Some(session) if session.is_connected() => session,
+            _ => return,
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        session.running_since = Some(Instant::now());
+        session.step_until = Some(StepUntilProgress {
+            expression: step_until.expression.clone(),
+            steps: 0,
+            max_steps: step_until.max_steps,
+            cancel: Arc::clone(&cancel),
+        });
+        let id = session.id;
+        let client = Arc::clone(&session.client);
+
+        tokio::spawn(async move {
+            let mut last_response: Option<ContinuationResponse> = None;
+            let mut outcome = StepUntilOutcome::MaxSteps(step_until.max_steps);
+            for step in 1..=step_until.max_steps {
+                if cancel.load(Ordering::Relaxed) {
+                    outcome = StepUntilOutcome::Interrupted(step - 1);
+                    break;
+                }
+                let pending = {
+                    let mut client = client.lock().await;
+                    match step_until.step_into {
+                        true => client.step_into().await,
+                        false => client.step_over().await,
+                    }
+                };
+                let response = match pending {
+                    Ok(pending) => pending.continuation().await,
+                    Err(e) => Err(e),
+                };
+                let response = match response {
+                    Ok(response) => response,
+                    Err(e) => {
+                        outcome = StepUntilOutcome::Error(e.to_string());
+                        break;
+                    }
+                };
+                let status = response.status.clone();
+                last_response = Some(response);
+                if status != ContinuationStatus::Break {
+                    outcome = StepUntilOutcome::Ended(step);
+                    break;
+                }
+                sender.send(AppEvent::StepUntilProgress(id, step)).await.unwrap();
+
+                let eval = client
+                    .lock()
+                    .await
+                    .eval(step_until.expression.clone(), 0)
+                    .await;
+                match eval {
+                    Ok(EvalResponse {
+                        error: Some(error), ..
+                    }) => {
+                        outcome = StepUntilOutcome::Error(error.message);
+                        break;
+                    }
+                    Ok(response) => {
+                        if response.properties.properties.first().is_some_and(|p| p.is_truthy()) {
+                            outcome = StepUntilOutcome::True(step);
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        outcome = StepUntilOutcome::Error(e.to_string());
+                        break;
+                    }
+                }
+            }
+            if last_response.as_ref().is_some_and(|r| r.status == ContinuationStatus::Break) {
+                sender.send(AppEvent::Snapshot(id)).await.unwrap();
+            }
+            sender.send(AppEvent::StepUntilDone(id, outcome)).await.unwrap();
+            if let Some(last_response) = last_response {
+                sender
+                    .send(AppEvent::UpdateStatus(id, last_response))
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
     // generically handle "continuation" events and update the
     // application state accordingly.
     async fn exec_continuation(&mut self, event: AppEvent) {
-        let client = Arc::clone(&self.client);
-        let sender = self.sender.clone();
         let count = self.take_motion();
+        if self.warn_if_running() {
+            return;
+        }
+        let sender = self.sender.clone();
+        // breakpoints are always honoured, only steps skip filtered files
+        let step_filters = match event {
+            AppEvent::Run => StepFilters::default(),
+            _ => self.config.step_filters.clone(),
+        };
+        let path_mappings = self.path_mappings.clone();
+        let session = match self.session_mut() {
+            Some(session) if session.is_connected() => session,
+            _ => return,
+        };
+        session.running_since = Some(Instant::now());
+        let id = session.id;
+        let client = Arc::clone(&session.client);
 
-        let snapshot_notify = Arc::clone(&self.snapshot_notify);
+        let snapshot_notify = Arc::clone(&session.snapshot_notify);
         snapshot_notify.notify_one();
 
         tokio::spawn(async move {
@@ -616,7 +1463,7 @@ impl App {
                 snapshot_notify.notified().await;
 
                 info!("Running iteration {}/{}", i, count);
-                let response = {
+                let pending = {
                     let mut instance = client.lock().await;
                     match event {
                         AppEvent::Run => instance.deref_mut().run().await,
@@ -626,15 +1473,34 @@ impl App {
                         _ => panic!("Unexpected continuation event: {:?}", event),
                     }
                 };
+                // the client is released while the engine is running so
+                // that further commands (e.g. `break`) can be sent
+                let response = match pending {
+                    Ok(pending) => pending.continuation().await,
+                    Err(e) => Err(e),
+                };
+                let response = match response {
+                    Ok(response) if !step_filters.is_empty() => {
+                        skip_filtered_frames(
+                            &client,
+                            &step_filters,
+                            &path_mappings,
+                            response,
+                            MAX_FILTERED_STEPS,
+                        )
+                        .await
+                    }
+                    response => response,
+                };
 
                 match response {
                     Ok(response) => {
                         last_response = Some(response.clone());
                         match response.status {
                             ContinuationStatus::Break => {
-                                sender.send(AppEvent::Snapshot()).await.unwrap();
+                                sender.send(AppEvent::Snapshot(id)).await.unwrap();
                             }
-                            ContinuationStatus::Stopping => {
+                            ContinuationStatus::Stopping | ContinuationStatus::Stopped => {
                                 break;
                             }
                             _ => (),
@@ -642,13 +1508,13 @@ impl App {
                         continue;
                     }
                     Err(_) => {
-                        sender.send(AppEvent::Disconnect).await.unwrap();
+                        sender.send(AppEvent::Disconnect(id)).await.unwrap();
                     }
                 };
             }
             if let Some(last_response) = last_response {
                 sender
-                    .send(AppEvent::UpdateStatus(last_response.status))
+                    .send(AppEvent::UpdateStatus(id, last_response))
                     .await
                     .unwrap();
             }
@@ -659,6 +1525,10 @@ impl App {
         if let Some(dialog) = &self.active_dialog {
             let subsequent_event = match &dialog {
                 ActiveDialog::Eval => EvalDialog::handle(self, event),
+                ActiveDialog::Breakpoint => BreakpointDialog::handle(self, event),
+                ActiveDialog::Property => PropertyDialog::handle(self, event),
+                ActiveDialog::Watch => WatchDialog::handle(self, event),
+                ActiveDialog::StepUntil => StepUntilDialog::handle(self, event),
             };
             if let Some(event) = subsequent_event {
                 self.sender.send(event).await.unwrap()
@@ -672,6 +1542,8 @@ impl App {
             SelectedView::Help => HelpView::handle(self, event),
             SelectedView::Listen => ListenView::handle(self, event),
             SelectedView::Session => SessionView::handle(self, event),
+            SelectedView::Sessions => SessionsView::handle(self, event),
+            SelectedView::Timeline => TimelineView::handle(self, event),
         };
         if let Some(event) = subsequent_event {
             self.sender.send(event).await.unwrap()
@@ -694,35 +1566,55 @@ impl App {
         }
     }
 
-    /// capture the current status and push it onto the history stack
-    pub async fn snapshot(&mut self) -> Result<()> {
-        let stack = { self.client.lock().await.deref_mut().get_stack().await? };
+    /// capture the current status of the session and push it onto its
+    /// history stack
+    pub async fn snapshot(&mut self, id: SessionId) -> Result<()> {
+        let index = match self.sessions.iter().position(|s| s.id == id) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let is_current = self.current_session == Some(index);
+        // *xdebug* only evalutes expressions on the current stack frame
+        let stack_depth = match is_current {
+            true => self.session_view.stack_depth(),
+            false => 0,
+        };
+        let context_id = self.session_view.context_id;
+        let session = &mut self.sessions[index];
+
+        let stack = { session.client.lock().await.deref_mut().get_stack().await? };
         let mut entry = HistoryEntry::new();
         for (level, frame) in stack.entries.iter().enumerate() {
             let filename = &frame.filename;
             let line_no = frame.line;
-            let context = {
-                match (level as u16) < self.stack_max_context_fetch {
-                    true => Some(
-                        self.client
-                            .lock()
-                            .await
-                            .deref_mut()
-                            .context_get(level as u16)
-                            .await?,
-                    ),
-                    false => None,
+            let mut contexts = HashMap::new();
+            if (level as u16) < self.stack_max_context_fetch {
+                // locals are used for the inline values, other contexts are
+                // only fetched when they are shown
+                let mut context_ids = vec![0];
+                if is_current && level as u16 == stack_depth && context_id != 0 {
+                    context_ids.push(context_id);
                 }
-            };
+                for id in context_ids {
+                    let context = session
+                        .client
+                        .lock()
+                        .await
+                        .deref_mut()
+                        .context_get(level as u16, id)
+                        .await?;
+                    contexts.insert(id, context);
+                }
+            }
 
-            let document = self.workspace.open(filename.to_string()).await;
+            let document = session.workspace.open(filename.to_string()).await?;
             let source = SourceContext {
                 source: document.text.to_string(),
                 filename: document.filename.to_string(),
                 line_no,
             };
 
-            match self.analyzed_files.entry(filename.clone()) {
+            match session.analyzed_files.entry(filename.clone()) {
                 Entry::Occupied(_) => (),
                 Entry::Vacant(vacant_entry) => {
                     let mut analyser = Analyser::new();
@@ -730,12 +1622,13 @@ impl App {
                 }
             };
 
-            let analysis = self.analyzed_files.get(&filename.clone());
+            let analysis = session.analyzed_files.get(&filename.clone());
 
             let stack = StackFrame {
                 level: (level as u16),
                 source,
-                context,
+                function: frame.function.clone(),
+                contexts,
             };
 
             {
@@ -749,23 +1642,19 @@ impl App {
                         }
                     }
 
-                    self.document_variables.put(&stack.source, vars);
+                    session.document_variables.put(&stack.source, vars);
                 }
             }
 
             entry.push(stack);
         }
 
-        // *xdebug* only evalutes expressions on the current stack frame
         let eval = if !self.session_view.eval_state.input.to_string().is_empty() {
-            let response = self
+            let response = session
                 .client
                 .lock()
                 .await
-                .eval(
-                    self.session_view.eval_state.input.to_string(),
-                    self.session_view.stack_depth(),
-                )
+                .eval(self.session_view.eval_state.input.to_string(), stack_depth)
                 .await?;
 
                 Some(EvalEntry{
@@ -777,49 +1666,582 @@ impl App {
         };
 
         entry.eval = eval;
+        entry.watches =
+            eval_watches(session.client.lock().await.deref_mut(), &self.watches, stack_depth).await;
+        session.history.push(entry);
+        let notifications = session.collect_output().await;
+        self.notify(notifications, is_current);
+
+        if is_current {
+            self.session_view.reset();
+            self.recenter();
+        }
+        Ok(())
+    }
+
+    /// record the status of a session once a continuation has finished
+    async fn update_status(&mut self, id: SessionId, response: ContinuationResponse) -> Result<()> {
+        if let ContinuationStatus::Stopping | ContinuationStatus::Stopped = response.status {
+            self.sender.send(AppEvent::Disconnect(id)).await.unwrap();
+        }
+        let is_break = response.status == ContinuationStatus::Break;
+        let exception = response.exception.is_some();
+        if let Some(session) = self.session_by_id_mut(id) {
+            session.running_since = None;
+            session.server_status = Some(response.status);
+            session.server_exception = response.exception;
+            let cleared = match is_break {
+                true => session.clear_temporary_breakpoint().await,
+                false => Ok(()),
+            };
+            session.temporary_breakpoint = None;
+            if let Err(e) = cleared {
+                self.notification = Notification::warning(format!(
+                    "Could not remove the run to cursor breakpoint: {}",
+                    e
+                ));
+            }
+        }
+        if is_break {
+            if let Err(e) = self.sync_breakpoints(id, exception).await {
+                self.notification =
+                    Notification::warning(format!("Could not list breakpoints: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    /// refresh the hit counts of the breakpoints if the current session
+    /// stopped at one of them
+    async fn sync_breakpoints(&mut self, id: SessionId, exception: bool) -> Result<()> {
+        let session = match self.session() {
+            Some(session) if session.id == id && session.is_connected() => session,
+            _ => return Ok(()),
+        };
+        let frame = match session.history.entries.last().and_then(|entry| entry.stack(0)) {
+            Some(frame) => frame,
+            None => return Ok(()),
+        };
+        let filename = self.path_mappings.local_uri(&frame.source.filename);
+        if !self.breakpoints.stopped_at(
+            &filename,
+            frame.source.line_no,
+            frame.function.as_deref(),
+            exception,
+        ) {
+            return Ok(());
+        }
+        let client = Arc::clone(&session.client);
+        let breakpoints = client.lock().await.breakpoint_list().await?;
+        self.breakpoints.sync(breakpoints);
+        Ok(())
+    }
+
+    /// show notifications from the engine, resolved breakpoints update the
+    /// breakpoints of the current session
+    fn notify(&mut self, notifications: Vec<EngineNotify>, is_current: bool) {
+        for notify in notifications {
+            info!("engine notification: {:?}", notify);
+            match notify {
+                EngineNotify::Error(error) => {
+                    self.notification = Notification::engine_error(&error);
+                }
+                EngineNotify::BreakpointResolved(breakpoint) if is_current => {
+                    self.breakpoints.sync(vec![breakpoint]);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    async fn toggle_breakpoint(&mut self, filename: String, line_no: u32) -> Result<()> {
+        if line_no == 0 {
+            return Ok(());
+        }
+
+        // breakpoints are stored with local paths and translated when they
+        // are set on the engine
+        let filename = self.path_mappings.local_uri(&filename);
+        if let Some(breakpoint) = self.breakpoints.remove_line(&filename, line_no) {
+            if let (Some(id), Some(client)) = (breakpoint.id, self.connected_client()) {
+                client.lock().await.breakpoint_remove(&id).await?;
+            }
+            self.notification = Notification::info(format!("Removed breakpoint on line {}", line_no));
+            return Ok(());
+        }
+
+        let mut breakpoint = Breakpoint::line(filename, line_no);
+        if let Some(client) = self.connected_client() {
+            let response = client
+                .lock()
+                .await
+                .breakpoint_set(&self.path_mappings.remote_breakpoint(&breakpoint))
+                .await?;
+            breakpoint.id = Some(response.id);
+        }
+        self.breakpoints.add(breakpoint);
+        self.notification = Notification::info(format!("Set breakpoint on line {}", line_no));
+        Ok(())
+    }
+
+    // (re)set the breakpoint on the engine, the engine does not allow
+    // the condition of an existing breakpoint to be updated
+    async fn replace_breakpoint(&mut self, mut breakpoint: Breakpoint) -> Result<()> {
+        let client = self.connected_client();
+        if let Some(existing) = self.breakpoints.remove(&breakpoint) {
+            if let (Some(id), Some(client)) = (existing.id, &client) {
+                client.lock().await.breakpoint_remove(&id).await?;
+            }
+        }
+        breakpoint.id = None;
+        if let Some(client) = client {
+            let response = client
+                .lock()
+                .await
+                .breakpoint_set(&self.path_mappings.remote_breakpoint(&breakpoint))
+                .await?;
+            breakpoint.id = Some(response.id);
+        }
+        self.notification = Notification::info(format!(
+            "Set breakpoint {}",
+            breakpoint_label(&breakpoint)
+        ));
+        self.breakpoints.add(breakpoint);
+        Ok(())
+    }
+
+    // make the session at the given index the current session. The engine
+    // IDs of our breakpoints are swapped and any breakpoints changed in the
+    // meantime are applied to the engine.
+    async fn switch_session(&mut self, index: usize) -> Result<()> {
+        if let Some(current) = self.current_session.and_then(|c| self.sessions.get_mut(c)) {
+            current.breakpoint_ids = self.breakpoints.take_ids();
+        }
+        self.current_session = Some(index);
+
+        let session = &mut self.sessions[index];
+        let stale = self
+            .breakpoints
+            .restore_ids(std::mem::take(&mut session.breakpoint_ids));
+        if session.is_connected() {
+            let mut client = session.client.lock().await;
+            for id in stale {
+                client.breakpoint_remove(&id).await?;
+            }
+            for breakpoint in self.breakpoints.iter_mut().filter(|b| b.id.is_none()) {
+                let response = client
+                    .breakpoint_set(&self.path_mappings.remote_breakpoint(breakpoint))
+                    .await?;
+                breakpoint.id = Some(response.id);
+            }
+        }
+
+        self.session_view.mode = match session.is_connected() && session.history.is_current() {
+            true => SessionViewMode::Current,
+            false => SessionViewMode::History,
+        };
+        self.view_current = SelectedView::Session;
         self.session_view.reset();
-        self.history.push(entry);
         self.recenter();
         Ok(())
     }
 
+    async fn close_session(&mut self, index: usize) {
+        let session = self.sessions.remove(index);
+        if session.is_connected() {
+            let _ = session.client.lock().await.deref_mut().disonnect().await;
+        }
+        self.notification = Notification::info(format!("Closed session {}", session.label()));
+        self.sessions_scroll.0 = self
+            .sessions_scroll
+            .0
+            .min(self.sessions.len().saturating_sub(1) as u16);
+        self.current_session = match self.current_session {
+            Some(current) if current == index => {
+                // the engine IDs belonged to the closed session
+                self.breakpoints.take_ids();
+                None
+            }
+            Some(current) if current > index => Some(current - 1),
+            current => current,
+        };
+        if self.current_session.is_none() {
+            self.view_current = match self.sessions.is_empty() {
+                true => SelectedView::Listen,
+                false => SelectedView::Sessions,
+            };
+        }
+    }
+
+    // register our listener with the DBGp proxy, if configured
+    async fn proxy_init(&mut self) -> Result<()> {
+        if let (Some(proxy), Some(idekey)) = (&self.config.proxy, &self.config.idekey) {
+            let response = proxy_init(proxy, self.config.listen_port()?, idekey).await?;
+            self.notification = Notification::info(format!(
+                "Registered with proxy {} as {}",
+                proxy, response.idekey
+            ));
+        }
+        Ok(())
+    }
+
+    fn load_state(&mut self) -> Result<()> {
+        let state = State::load(&self.config.state_path())?;
+        if let Some(theme) = state.theme {
+            self.theme = theme;
+        }
+        if let Some(context_depth) = state.context_depth {
+            self.context_depth = context_depth.clamp(1, 9);
+        }
+        if let Some(eval) = state.eval {
+            self.session_view.eval_state.input = Input::new(eval);
+        }
+        self.breakpoints.breakpoints = state.breakpoints;
+        self.watches = state.watches;
+        Ok(())
+    }
+
+    // the theme and context depth are only saved when they were changed at
+    // runtime, otherwise the configuration would be ignored from then on
+    fn save_state(&self) -> Result<()> {
+        State {
+            theme: Some(self.theme.clone()).filter(|theme| *theme != self.config.theme),
+            context_depth: Some(self.context_depth)
+                .filter(|depth| *depth != self.config.context_depth),
+            eval: match self.session_view.eval_state.input.value() {
+                "" => None,
+                eval => Some(eval.to_string()),
+            },
+            breakpoints: self.breakpoints.breakpoints.clone(),
+            watches: self.watches.clone(),
+        }
+        .save(&self.config.state_path())
+    }
+
     pub(crate) fn theme(&self) -> Scheme {
         self.theme.scheme()
     }
 
+    pub(crate) fn keymap(&self) -> &Keymap {
+        &self.config.keymap
+    }
+
+    pub(crate) fn session(&self) -> Option<&Session> {
+        self.current_session.and_then(|index| self.sessions.get(index))
+    }
+
+    pub(crate) fn session_mut(&mut self) -> Option<&mut Session> {
+        self.current_session.and_then(|index| self.sessions.get_mut(index))
+    }
+
+    fn session_by_id(&self, id: SessionId) -> Option<&Session> {
+        self.sessions.iter().find(|s| s.id == id)
+    }
+
+    fn session_by_id_mut(&mut self, id: SessionId) -> Option<&mut Session> {
+        self.sessions.iter_mut().find(|s| s.id == id)
+    }
+
+    // the client of the current session if it is connected
+    fn connected_client(&self) -> Option<Arc<Mutex<DbgpClient>>> {
+        self.session()
+            .filter(|s| s.is_connected())
+            .map(|s| Arc::clone(&s.client))
+    }
+
+    /// the selected history entry of the current session
+    pub(crate) fn history_entry(&self) -> Option<&HistoryEntry> {
+        self.session().and_then(|s| s.history.current())
+    }
+
     async fn populate_stack_context(&mut self) -> Result<()> {
-        if !self.history.is_current() {
+        let level = self.session_view.stack_level();
+        let context_id = self.session_view.context_id;
+        let session = match self.current_session.and_then(|index| self.sessions.get_mut(index)) {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+        // the context is shown as not fetched until the next stop
+        if !session.is_connected() || session.running_since.is_some() || !session.history.is_current() {
             return Ok(());
         }
-        let level = self.session_view.stack_level();
-        if let Some(c) = self.history.current_mut() {
+        if let Some(c) = session.history.current_mut() {
             let stack = c.stacks.get_mut(level);
             if let Some(s) = stack {
-                if s.context.is_none() {
-                    let mut client = self.client.lock().await;
-                    let context = client.deref_mut().context_get(level as u16).await?;
-                    s.context = Some(context);
+                if let Entry::Vacant(entry) = s.contexts.entry(context_id) {
+                    let mut client = session.client.lock().await;
+                    let context = client.deref_mut().context_get(level as u16, context_id).await?;
+                    entry.insert(context);
                 }
             };
         };
         Ok(())
     }
 
-    fn reset(&mut self) {
-        self.server_status = None;
-        self.view_current = SelectedView::Session;
-        self.session_view.mode = SessionViewMode::Current;
-        self.analyzed_files = HashMap::new();
-        self.workspace.reset();
+    /// open the dialog for the selected watch
+    fn edit_watch(&mut self) {
+        let index = self.session_view.watches_scroll.0 as usize;
+        if let Some(expr) = self.watches.get(index) {
+            self.session_view.watch_dialog = WatchDialogState {
+                index: Some(index),
+                input: Input::new(expr.clone()),
+            };
+            self.active_dialog = Some(ActiveDialog::Watch);
+        }
+    }
+
+    fn delete_watch(&mut self) {
+        let index = self.session_view.watches_scroll.0 as usize;
+        if index < self.watches.len() {
+            self.watches.remove(index);
+            self.session_view.watches_scroll.0 = self
+                .session_view
+                .watches_scroll
+                .0
+                .min(self.watches.len().saturating_sub(1) as u16);
+        }
+    }
+
+    /// add or replace the watch of the dialog and select it
+    async fn apply_watch_dialog(&mut self) -> Result<()> {
+        self.active_dialog = None;
+        let expr = self.session_view.watch_dialog.input.value().trim().to_string();
+        if expr.is_empty() {
+            return Ok(());
+        }
+        let index = match self.session_view.watch_dialog.index {
+            Some(index) if index < self.watches.len() => {
+                self.watches[index] = expr;
+                index
+            }
+            _ => {
+                self.watches.push(expr);
+                self.watches.len() - 1
+            }
+        };
+        self.session_view.watches_scroll.0 = index as u16;
+        self.evaluate_watches().await
+    }
+
+    /// evaluate the watches for the current step, e.g. after they have been
+    /// changed
+    async fn evaluate_watches(&mut self) -> Result<()> {
+        let depth = self.session_view.stack_depth();
+        let watches = self.watches.clone();
+        let session = match self.session_mut() {
+            Some(session)
+                if session.is_connected()
+                    && session.history.is_current()
+                    && session.running_since.is_none() =>
+            {
+                session
+            }
+            _ => return Ok(()),
+        };
+        let evaluated = eval_watches(session.client.lock().await.deref_mut(), &watches, depth).await;
+        if let Some(entry) = session.history.current_mut() {
+            entry.watches = evaluated;
+        }
+        Ok(())
+    }
+
+    /// set the property being edited and update it in the current step with
+    /// the value reported by the engine
+    async fn set_property(&mut self, value: &str) -> Result<()> {
+        let level = self.session_view.stack_level();
+        let context_id = self.session_view.context_id;
+        let state = &self.session_view.property_dialog;
+        let session = match self.current_session.and_then(|index| self.sessions.get_mut(index)) {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+        let fetched = {
+            let mut client = session.client.lock().await;
+            client
+                .property_set(&state.fullname, level as u16, context_id, state.property_type, value)
+                .await?;
+            client.property_get(&state.fullname, 0, level as u16, context_id).await?
+        };
+        let property = session
+            .history
+            .current_mut()
+            .and_then(|entry| entry.stacks.get_mut(level))
+            .and_then(|stack| stack.contexts.get_mut(&context_id))
+            .and_then(|context| context.properties.find_mut(&state.fullname));
+        if let Some(property) = property {
+            property.property_type = fetched.property_type;
+            property.value = fetched.value;
+        }
+        Ok(())
+    }
+
+    /// fetch the next page of children of a property of the selected stack
+    /// frame, properties can only be fetched for the current step
+    async fn fetch_children(&mut self, fullname: &str) -> Result<()> {
+        let level = self.session_view.stack_level();
+        let context_id = self.session_view.context_id;
+        let session = match self.current_session.and_then(|index| self.sessions.get_mut(index)) {
+            Some(session) if session.is_connected() && session.history.is_current() => session,
+            _ => {
+                self.notification = Notification::warning(
+                    "Properties can only be fetched in the current step".to_string(),
+                );
+                return Ok(());
+            }
+        };
+        let property = session
+            .history
+            .current_mut()
+            .and_then(|entry| entry.stacks.get_mut(level))
+            .and_then(|stack| stack.contexts.get_mut(&context_id))
+            .and_then(|context| context.properties.find_mut(fullname));
+        let property = match property {
+            Some(property) => property,
+            None => return Ok(()),
+        };
+
+        let fetched = property.children.properties.len() as u32;
+        let page = fetched / property.pagesize.unwrap_or(fetched).max(1);
+        let response = session
+            .client
+            .lock()
+            .await
+            .property_get(fullname, page, level as u16, context_id)
+            .await?;
+
+        property.numchildren = response.numchildren.or(property.numchildren);
+        property.pagesize = response.pagesize.or(property.pagesize);
+        match page {
+            0 => property.children = response.children,
+            _ => property.children.properties.extend(response.children.properties),
+        }
+        Ok(())
+    }
+
+    /// the source currently shown in the source pane
+    pub(crate) fn source(&self) -> Option<SourceContext> {
+        if let Some(source) = &self.session_view.source_override {
+            return Some(source.clone());
+        }
+        self.history_entry()
+            .map(|entry| entry.source(self.session_view.stack_depth()))
+    }
+
+    // show the given file in the source pane, the source pane will return
+    // to the current stack frame when recentered
+    async fn jump_to_source(&mut self, filename: String, line_no: u32) -> Result<()> {
+        let current = self
+            .history_entry()
+            .map(|entry| entry.source(self.session_view.stack_depth()));
+        let session = match self.current_session.and_then(|index| self.sessions.get_mut(index)) {
+            Some(session) => session,
+            None => anyhow::bail!("Not connected to a session"),
+        };
+        self.session_view.source_override = match current {
+            Some(current) if current.filename == filename => None,
+            _ => {
+                let document = session.workspace.open(filename.clone()).await?;
+                Some(SourceContext {
+                    source: document.text.clone(),
+                    filename: document.filename.clone(),
+                    line_no: 0,
+                })
+            }
+        };
+        self.session_view.scroll_to_line(line_no);
+        Ok(())
     }
 
     fn recenter(&mut self) {
-        let entry = self.history.current();
-        if let Some(entry) = entry {
-            self.session_view
-                .scroll_to_line(entry.source(self.session_view.stack_depth()).line_no)
+        self.session_view.source_override = None;
+        let line_no = self
+            .history_entry()
+            .map(|entry| entry.source(self.session_view.stack_depth()).line_no);
+        if let Some(line_no) = line_no {
+            self.session_view.scroll_to_line(line_no)
+        }
+    }
+}
+
+// set all of our breakpoints on a newly connected engine and return their
+// engine IDs
+async fn set_breakpoints(
+    client: &mut DbgpClient,
+    breakpoints: &Breakpoints,
+    path_mappings: &PathMappings,
+) -> Result<HashMap<String, String>> {
+    let mut ids = HashMap::new();
+    for breakpoint in breakpoints.breakpoints.iter() {
+        info!("setting breakpoint {:?}", breakpoint);
+        let response = client
+            .breakpoint_set(&path_mappings.remote_breakpoint(breakpoint))
+            .await?;
+        ids.insert(breakpoint_key(breakpoint), response.id);
+    }
+    Ok(ids)
+}
+
+/// the maximum number of steps taken to leave filtered files
+const MAX_FILTERED_STEPS: usize = 1000;
+
+// keep stepping while the engine is in a filtered file. Filtered code which
+// was called from unfiltered code is stepped out of, otherwise we step into
+// until unfiltered code (e.g. a controller called by the framework) is reached.
+async fn skip_filtered_frames(
+    client: &Mutex<DbgpClient>,
+    step_filters: &StepFilters,
+    path_mappings: &PathMappings,
+    mut response: ContinuationResponse,
+    max_steps: usize,
+) -> Result<ContinuationResponse> {
+    for _ in 0..max_steps {
+        if response.status != ContinuationStatus::Break {
+            break;
+        }
+        let stack = client.lock().await.get_stack().await?;
+        let is_filtered = |level: usize| {
+            stack
+                .entries
+                .get(level)
+                .is_some_and(|entry| step_filters.is_filtered_remote(&entry.filename, path_mappings))
+        };
+        if !is_filtered(0) {
+            break;
         }
+        let pending = {
+            let mut client = client.lock().await;
+            match stack.entries.len() > 1 && !is_filtered(1) {
+                true => client.step_out().await?,
+                false => client.step_into().await?,
+            }
+        };
+        response = pending.continuation().await?;
+    }
+    Ok(response)
+}
+
+// evaluate each watch expression, errors (including those of the connection)
+// are kept with the expression so that the step is not lost
+async fn eval_watches(client: &mut DbgpClient, watches: &[String], depth: u16) -> Vec<EvalEntry> {
+    let mut entries = vec![];
+    for expr in watches {
+        let response = match client.eval(expr.clone(), depth).await {
+            Ok(response) => response,
+            Err(e) => EvalResponse {
+                success: false,
+                error: Some(DbgpError {
+                    message: e.to_string(),
+                    code: String::new(),
+                }),
+                properties: Properties::none(),
+            },
+        };
+        entries.push(EvalEntry {
+            expr: expr.clone(),
+            response,
+        });
     }
+    entries
 }
 
 fn apply_scroll(scroll: (u16, u16), amount: (i16, i16), motion: i16) -> (u16, u16) {
@@ -828,3 +2250,353 @@ fn apply_scroll(scroll: (u16, u16), amount: (i16, i16), motion: i16) -> (u16, u1
         (scroll.1 as i16).saturating_add(amount.1 * motion).max(0) as u16,
     )
 }
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::dbgp::client::test::conversing_engine;
+    use crate::dbgp::client::PropertyType;
+    use crate::view::properties::Change;
+    use crate::workspace::PathMapping;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tokio::sync::mpsc::channel;
+
+    pub(crate) fn app(config: Config) -> App {
+        let (sender, receiver) = channel(16);
+        App::new(config, receiver, sender)
+    }
+
+    /// a step at line 1 with the given locals
+    pub(crate) fn entry(locals: Vec<Property>) -> HistoryEntry {
+        let mut entry = HistoryEntry::initial("file:///app/foo.php".to_string(), String::new());
+        entry.stacks[0].source.line_no = 1;
+        entry.stacks[0].contexts.insert(
+            0,
+            ContextGetResponse {
+                properties: Properties::from_properties(locals),
+            },
+        );
+        entry
+    }
+
+    pub(crate) fn connect(app: &mut App, client: DbgpClient, entries: Vec<HistoryEntry>) {
+        let mut session = Session::new(0, PathMappings::new(vec![]));
+        session.client = Arc::new(Mutex::new(client));
+        for entry in entries {
+            session.history.push(entry);
+        }
+        app.sessions.push(session);
+        app.current_session = Some(0);
+    }
+
+    #[tokio::test]
+    async fn test_set_property_updates_the_current_step() -> Result<()> {
+        let (client, engine) = conversing_engine(&[
+            r#"<response command="property_set" transaction_id="0" success="1"></response>"#,
+            r#"<response command="property_get" transaction_id="1"><property name="$foo" fullname="$foo" type="int"><![CDATA[5]]></property></response>"#,
+        ])
+        .await?;
+        let foo = Property {
+            name: "$foo".to_string(),
+            fullname: "$foo".to_string(),
+            property_type: PropertyType::Int,
+            value: Some("1".to_string()),
+            ..Default::default()
+        };
+        let mut app = app(Config::default());
+        app.session_view.property_dialog = PropertyDialogState::edit(&foo)?;
+        connect(&mut app, client, vec![entry(vec![foo])]);
+
+        app.set_property("5").await?;
+
+        assert_eq!(
+            vec![
+                r#"property_set -i 0 -d 0 -c 0 -t int -n "$foo" -- NQ=="#,
+                r#"property_get -i 1 -d 0 -c 0 -p 0 -n "$foo""#,
+            ],
+            engine.await?
+        );
+        // no step is added for the new value
+        let history = &app.session().unwrap().history;
+        assert_eq!(1, history.len());
+        let locals = &history.current().unwrap().stacks[0].context(0).unwrap().properties;
+        assert_eq!(Some("5"), locals.get("$foo").unwrap().value.as_deref());
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_does_not_override_config() -> Result<()> {
+        let mut config = Config {
+            project_dir: std::env::temp_dir().join(format!("debug-tui-app-{}", std::process::id())),
+            theme: Theme::Dark,
+            context_depth: 2,
+            ..Default::default()
+        };
+
+        // nothing was changed at runtime
+        app(config.clone()).save_state()?;
+        assert_eq!(None, State::load(&config.state_path())?.theme);
+        assert_eq!(None, State::load(&config.state_path())?.context_depth);
+
+        // the configuration is changed after the state was saved
+        config.theme = Theme::SolarizedLight;
+        config.context_depth = 3;
+        let mut app = app(config.clone());
+        app.load_state()?;
+        assert_eq!(Theme::SolarizedLight, app.theme);
+        assert_eq!(3, app.context_depth);
+
+        // runtime changes are kept
+        app.theme = Theme::Dark;
+        app.save_state()?;
+        let mut app = self::app(config.clone());
+        app.load_state()?;
+        assert_eq!(Theme::Dark, app.theme);
+        assert_eq!(3, app.context_depth);
+
+        fs::remove_dir_all(&config.project_dir)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_clear_temporary_breakpoint() -> Result<()> {
+        let mut breakpoint = Breakpoint::line("file:///app/foo.php".to_string(), 1);
+        breakpoint.id = Some("42".to_string());
+        breakpoint.temporary = true;
+
+        // the run stopped at the breakpoint, the engine removed it
+        let (client, engine) = conversing_engine(&[]).await?;
+        let mut app = app(Config::default());
+        connect(&mut app, client, vec![entry(vec![])]);
+        let session = app.session_mut().unwrap();
+        session.temporary_breakpoint = Some(breakpoint.clone());
+        session.clear_temporary_breakpoint().await?;
+        assert_eq!(0, engine.await?.len());
+
+        // the run stopped elsewhere
+        let (client, engine) = conversing_engine(&[
+            r#"<response command="breakpoint_remove" transaction_id="0"></response>"#,
+        ])
+        .await?;
+        let mut app = self::app(Config::default());
+        connect(&mut app, client, vec![entry(vec![])]);
+        let session = app.session_mut().unwrap();
+        breakpoint.line_no = Some(12);
+        session.temporary_breakpoint = Some(breakpoint);
+        session.clear_temporary_breakpoint().await?;
+        assert_eq!(vec!["breakpoint_remove -i 0 -d 42"], engine.await?);
+        assert!(session.temporary_breakpoint.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_status_when_listing_breakpoints_fails() -> Result<()> {
+        // the engine hangs up before the breakpoints are listed
+        let (client, engine) = conversing_engine(&[]).await?;
+        let mut app = app(Config::default());
+        app.breakpoints.add(Breakpoint::line("file:///app/foo.php".to_string(), 1));
+        connect(&mut app, client, vec![entry(vec![])]);
+        engine.await?;
+        app.session_mut().unwrap().running_since = Some(Instant::now());
+
+        app.update_status(0, ContinuationResponse {
+            status: ContinuationStatus::Break,
+            reason: "ok".to_string(),
+            exception: None,
+        })
+        .await?;
+
+        let session = app.session().unwrap();
+        assert!(session.running_since.is_none());
+        assert_eq!(Some(ContinuationStatus::Break), session.server_status);
+        assert!(app.notification.message.starts_with("Could not list breakpoints"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_status_when_removing_the_temporary_breakpoint_fails() -> Result<()> {
+        let (client, engine) = conversing_engine(&[]).await?;
+        let mut app = app(Config::default());
+        connect(&mut app, client, vec![entry(vec![])]);
+        engine.await?;
+        let session = app.session_mut().unwrap();
+        let mut breakpoint = Breakpoint::line("file:///app/foo.php".to_string(), 12);
+        breakpoint.id = Some("42".to_string());
+        session.temporary_breakpoint = Some(breakpoint);
+        session.running_since = Some(Instant::now());
+
+        app.update_status(0, ContinuationResponse {
+            status: ContinuationStatus::Break,
+            reason: "ok".to_string(),
+            exception: None,
+        })
+        .await?;
+
+        let session = app.session().unwrap();
+        assert!(session.running_since.is_none());
+        assert!(session.temporary_breakpoint.is_none());
+        assert!(app.notification.message.starts_with("Could not remove the run to cursor breakpoint"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_changes_are_only_diffed_within_the_same_function() {
+        let local = |value: &str| Property {
+            name: "$i".to_string(),
+            fullname: "$i".to_string(),
+            property_type: PropertyType::Int,
+            value: Some(value.to_string()),
+            ..Default::default()
+        };
+        let mut history = History::default();
+        let mut first = entry(vec![local("1")]);
+        first.stacks[0].function = Some("foo".to_string());
+        let mut second = entry(vec![local("2")]);
+        second.stacks[0].function = Some("foo".to_string());
+        history.push(first);
+        history.push(second);
+
+        let changes = history.changes(0, 0).unwrap();
+        assert_eq!(Some(Change::Changed), changes.get("$i"));
+        // cached until the step is changed
+        assert!(Rc::ptr_eq(&changes, &history.changes(0, 0).unwrap()));
+
+        // another function in the same file at the same depth
+        let mut third = entry(vec![local("3")]);
+        third.stacks[0].function = Some("bar".to_string());
+        history.push(third);
+        assert!(history.changes(0, 0).is_none());
+    }
+
+    fn break_response() -> ContinuationResponse {
+        ContinuationResponse {
+            status: ContinuationStatus::Break,
+            reason: "ok".to_string(),
+            exception: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_skip_filtered_frames() -> Result<()> {
+        let (client, engine) = conversing_engine(&[
+            // lib is only filtered by its local path
+            r#"<response command="stack_get" transaction_id="0"><stack where="Lib->call" level="0" type="file" filename="file:///var/www/lib/Lib.php" lineno="3"></stack><stack where="Kernel->handle" level="1" type="file" filename="file:///var/www/vendor/Kernel.php" lineno="5"></stack><stack where="{main}" level="2" type="file" filename="file:///var/www/index.php" lineno="2"></stack></response>"#,
+            r#"<response command="step_into" transaction_id="1" status="break" reason="ok"></response>"#,
+            r#"<response command="stack_get" transaction_id="2"><stack where="Lib->call" level="0" type="file" filename="file:///var/www/lib/Lib.php" lineno="4"></stack><stack where="{main}" level="1" type="file" filename="file:///var/www/index.php" lineno="2"></stack></response>"#,
+            r#"<response command="step_out" transaction_id="3" status="break" reason="ok"></response>"#,
+            r#"<response command="stack_get" transaction_id="4"><stack where="{main}" level="0" type="file" filename="file:///var/www/index.php" lineno="3"></stack></response>"#,
+        ])
+        .await?;
+        let step_filters = StepFilters::new(vec!["/var/www/vendor/*".to_string(), "/home/me/app/lib/*".to_string()]);
+        let path_mappings = PathMappings::new(vec![PathMapping {
+            remote: "/var/www".to_string(),
+            local: "/home/me/app".to_string(),
+        }]);
+
+        let client = Mutex::new(client);
+        let response =
+            skip_filtered_frames(&client, &step_filters, &path_mappings, break_response(), 10).await?;
+
+        assert_eq!(ContinuationStatus::Break, response.status);
+        // the caller of the first frame is filtered so we step into, the
+        // caller of the second is not so we step out
+        assert_eq!(
+            vec!["stack_get", "step_into", "stack_get", "step_out", "stack_get"],
+            engine.await?.iter().map(|c| c.split(' ').next().unwrap().to_string()).collect::<Vec<String>>()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_skip_filtered_frames_gives_up_after_max_steps() -> Result<()> {
+        let (client, engine) = conversing_engine(&[
+            r#"<response command="stack_get" transaction_id="0"><stack where="{main}" level="0" type="file" filename="file:///app/vendor/a.php" lineno="1"></stack></response>"#,
+            r#"<response command="step_into" transaction_id="1" status="break" reason="ok"></response>"#,
+            r#"<response command="stack_get" transaction_id="2"><stack where="{main}" level="0" type="file" filename="file:///app/vendor/a.php" lineno="2"></stack></response>"#,
+            r#"<response command="step_into" transaction_id="3" status="break" reason="ok"></response>"#,
+        ])
+        .await?;
+        let step_filters = StepFilters::new(vec!["*/vendor/*".to_string()]);
+
+        let client = Mutex::new(client);
+        let response =
+            skip_filtered_frames(&client, &step_filters, &PathMappings::default(), break_response(), 2).await?;
+
+        assert_eq!(ContinuationStatus::Break, response.status);
+        assert_eq!(4, engine.await?.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eval_watches() -> Result<()> {
+        let (mut client, engine) = conversing_engine(&[
+            r#"<response command="eval" transaction_id="0"><property type="int"><![CDATA[2]]></property></response>"#,
+            r#"<response command="eval" transaction_id="1"><error code="206"><message><![CDATA[error evaluating code]]></message></error></response>"#,
+        ])
+        .await?;
+        let watches = ["1 + 1", "$undefined->foo", "$foo"].map(String::from);
+        let evaluated = eval_watches(&mut client, &watches, 1).await;
+        assert_eq!(vec!["eval -i 0 -d 1 -- MSArIDE=", "eval -i 1 -d 1 -- JHVuZGVmaW5lZC0+Zm9v"], engine.await?);
+
+        assert_eq!(
+            vec!["1 + 1", "$undefined->foo", "$foo"],
+            evaluated.iter().map(|e| e.expr.as_str()).collect::<Vec<&str>>()
+        );
+        assert!(evaluated[0].response.error.is_none());
+        assert_eq!(Some("2"), evaluated[0].response.properties.properties[0].value.as_deref());
+        assert_eq!(
+            Some("error evaluating code"),
+            evaluated[1].response.error.as_ref().map(|e| e.message.as_str())
+        );
+        // the engine hung up, the error is kept with the watch
+        assert!(evaluated[2].response.error.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_edit_and_delete_watches() -> Result<()> {
+        let mut app = app(Config::default());
+        for expr in ["$foo", " ", "$bar"] {
+            app.session_view.watch_dialog = WatchDialogState {
+                index: None,
+                input: Input::new(expr.to_string()),
+            };
+            app.apply_watch_dialog().await?;
+        }
+        assert_eq!(vec!["$foo", "$bar"], app.watches);
+        assert_eq!(1, app.session_view.watches_scroll.0);
+
+        app.session_view.watches_scroll.0 = 0;
+        app.edit_watch();
+        assert_eq!(Some(0), app.session_view.watch_dialog.index);
+        assert_eq!("$foo", app.session_view.watch_dialog.input.value());
+        app.session_view.watch_dialog.input = Input::new("$foo->id".to_string());
+        app.apply_watch_dialog().await?;
+        assert_eq!(vec!["$foo->id", "$bar"], app.watches);
+
+        app.session_view.watches_scroll.0 = 1;
+        app.delete_watch();
+        assert_eq!(vec!["$foo->id"], app.watches);
+        assert_eq!(0, app.session_view.watches_scroll.0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_context_is_not_fetched_while_running_or_disconnected() -> Result<()> {
+        let mut app = app(Config::default());
+        // the client is not connected, a command would fail
+        connect(&mut app, DbgpClient::new(None), vec![entry(vec![])]);
+        app.session_view.context_id = 1;
+
+        app.session_mut().unwrap().running_since = Some(Instant::now());
+        app.populate_stack_context().await?;
+        app.session_mut().unwrap().running_since = None;
+        app.session_mut().unwrap().status = SessionStatus::Disconnected;
+        app.populate_stack_context().await?;
+
+        let entry = app.history_entry().unwrap();
+        assert!(entry.stacks[0].context(1).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use crate::dbgp::client::Breakpoint;
+use crate::dbgp::client::BreakpointType;
+use crate::dbgp::client::HitCondition;
+use std::collections::HashMap;
+
+/// Breakpoints set by the user. These outlive the debugging session and are
+/// re-applied to the engine whenever a new connection is made.
+#[derive(Default)]
+pub struct Breakpoints {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+impl Breakpoints {
+    pub fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Breakpoint> {
+        self.breakpoints.iter_mut()
+    }
+
+    pub fn add(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn at_line(&self, filename: &str, line_no: u32) -> Option<&Breakpoint> {
+        self.breakpoints.iter().find(|b| {
+            b.filename.as_deref() == Some(filename) && b.line_no == Some(line_no)
+        })
+    }
+
+    /// whether a stop at the given frame could have been caused by one of
+    /// the breakpoints, only then can their hit counts have changed
+    pub fn stopped_at(
+        &self,
+        filename: &str,
+        line_no: u32,
+        function: Option<&str>,
+        exception: bool,
+    ) -> bool {
+        // xdebug reports methods as `Foo->bar` in the stack
+        let function = function.map(|f| f.replace("->", "::"));
+        self.breakpoints.iter().any(|b| match b.breakpoint_type {
+            BreakpointType::Line | BreakpointType::Conditional => {
+                b.filename.as_deref() == Some(filename) && b.line_no == Some(line_no)
+            }
+            BreakpointType::Call | BreakpointType::Return => {
+                b.function.is_some() && b.function == function
+            }
+            BreakpointType::Exception => exception,
+            BreakpointType::Watch => false,
+        })
+    }
+
+    pub fn remove_line(&mut self, filename: &str, line_no: u32) -> Option<Breakpoint> {
+        let index = self.breakpoints.iter().position(|b| {
+            b.filename.as_deref() == Some(filename) && b.line_no == Some(line_no)
+        })?;
+        Some(self.breakpoints.remove(index))
+    }
+
+    /// remove the breakpoint with the same engine ID or, if it has not been
+    /// set on the engine, at the same location
+    pub fn remove(&mut self, breakpoint: &Breakpoint) -> Option<Breakpoint> {
+        let index = self.breakpoints.iter().position(|b| match &breakpoint.id {
+            Some(_) => b.id == breakpoint.id,
+            None => {
+                (b.breakpoint_type == breakpoint.breakpoint_type
+                    || (b.breakpoint_type.is_line() && breakpoint.breakpoint_type.is_line()))
+                    && b.filename == breakpoint.filename
+                    && b.line_no == breakpoint.line_no
+                    && b.function == breakpoint.function
+                    && b.exception == breakpoint.exception
+            }
+        })?;
+        Some(self.breakpoints.remove(index))
+    }
+
+    /// clear the engine IDs and return them keyed by [breakpoint_key], used
+    /// when switching to a different session
+    pub fn take_ids(&mut self) -> HashMap<String, String> {
+        self.breakpoints
+            .iter_mut()
+            .filter_map(|b| b.id.take().map(|id| (breakpoint_key(b), id)))
+            .collect()
+    }
+
+    /// restore the engine IDs of a session and return the IDs of the
+    /// breakpoints which have been removed since
+    pub fn restore_ids(&mut self, mut ids: HashMap<String, String>) -> Vec<String> {
+        for breakpoint in self.breakpoints.iter_mut() {
+            breakpoint.id = ids.remove(&breakpoint_key(breakpoint));
+        }
+        ids.into_values().collect()
+    }
+
+    /// update our breakpoints with the state reported by the engine
+    pub fn sync(&mut self, engine_breakpoints: Vec<Breakpoint>) {
+        for breakpoint in self.breakpoints.iter_mut() {
+            if breakpoint.id.is_none() {
+                continue;
+            }
+            if let Some(engine_breakpoint) = engine_breakpoints.iter().find(|b| b.id == breakpoint.id) {
+                breakpoint.state = engine_breakpoint.state.clone();
+                breakpoint.line_no = engine_breakpoint.line_no.or(breakpoint.line_no);
+                breakpoint.hit_count = engine_breakpoint.hit_count;
+            }
+        }
+    }
+}
+
+/// identifies a breakpoint by its type and location
+pub fn breakpoint_key(breakpoint: &Breakpoint) -> String {
+    format!(
+        "{}:{}:{}:{}:{}",
+        match breakpoint.breakpoint_type.is_line() {
+            true => "line",
+            false => breakpoint.breakpoint_type.as_str(),
+        },
+        breakpoint.filename.as_deref().unwrap_or_default(),
+        breakpoint.line_no.unwrap_or_default(),
+        breakpoint.function.as_deref().unwrap_or_default(),
+        breakpoint.exception.as_deref().unwrap_or_default(),
+    )
+}
+
+/// parse a hit condition such as `>= 10`, `== 5` or `% 2`. A value without
+/// an operator is treated as `>=`.
+pub fn parse_hit(input: &str) -> anyhow::Result<Option<(HitCondition, u32)>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    let (condition, value) = match input.find(|c: char| c.is_ascii_digit()) {
+        Some(offset) => input.split_at(offset),
+        None => anyhow::bail!("Hit condition must contain a value, e.g. \">= 10\""),
+    };
+    let condition = match condition.trim() {
+        "" => HitCondition::GreaterOrEqual,
+        c => match HitCondition::from_operator(c) {
+            Some(c) => c,
+            None => anyhow::bail!("Unknown hit condition \"{}\", use one of >=, == or %", c),
+        },
+    };
+    Ok(Some((condition, value.trim().parse::<u32>()?)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dbgp::client::BreakpointState;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_at_line_and_remove() {
+        let mut breakpoints = Breakpoints::default();
+        breakpoints.add(Breakpoint::line("file:///foo.php".to_string(), 10));
+        breakpoints.add(Breakpoint::line("file:///bar.php".to_string(), 10));
+
+        assert!(breakpoints.at_line("file:///foo.php", 10).is_some());
+        assert!(breakpoints.at_line("file:///foo.php", 11).is_none());
+
+        let removed = breakpoints.remove_line("file:///foo.php", 10);
+        assert_eq!(Some(Breakpoint::line("file:///foo.php".to_string(), 10)), removed);
+        assert!(breakpoints.at_line("file:///foo.php", 10).is_none());
+        assert!(breakpoints.at_line("file:///bar.php", 10).is_some());
+    }
+
+    #[test]
+    fn test_stopped_at() {
+        let mut breakpoints = Breakpoints::default();
+        assert!(!breakpoints.stopped_at("file:///foo.php", 10, None, true));
+
+        breakpoints.add(Breakpoint::line("file:///foo.php".to_string(), 10));
+        breakpoints.add(Breakpoint {
+            breakpoint_type: BreakpointType::Call,
+            function: Some("Foo::bar".to_string()),
+            ..Default::default()
+        });
+        assert!(breakpoints.stopped_at("file:///foo.php", 10, None, false));
+        assert!(!breakpoints.stopped_at("file:///foo.php", 11, None, false));
+        assert!(breakpoints.stopped_at("file:///foo.php", 11, Some("Foo->bar"), false));
+        assert!(!breakpoints.stopped_at("file:///foo.php", 11, Some("Foo->baz"), false));
+        assert!(!breakpoints.stopped_at("file:///foo.php", 11, None, true));
+
+        breakpoints.add(Breakpoint {
+            breakpoint_type: BreakpointType::Exception,
+            exception: Some("*".to_string()),
+            ..Default::default()
+        });
+        assert!(breakpoints.stopped_at("file:///foo.php", 11, None, true));
+    }
+
+    #[test]
+    fn test_sync() {
+        let mut breakpoints = Breakpoints::default();
+        let mut breakpoint = Breakpoint::line("file:///foo.php".to_string(), 10);
+        breakpoint.id = Some("1".to_string());
+        breakpoints.add(breakpoint.clone());
+        breakpoints.add(Breakpoint::line("file:///foo.php".to_string(), 12));
+
+        breakpoint.state = BreakpointState::Disabled;
+        breakpoint.line_no = Some(11);
+        breakpoints.sync(vec![breakpoint]);
+
+        assert_eq!(BreakpointState::Disabled, breakpoints.breakpoints[0].state);
+        assert_eq!(Some(11), breakpoints.breakpoints[0].line_no);
+        assert_eq!(BreakpointState::Enabled, breakpoints.breakpoints[1].state);
+    }
+
+    #[test]
+    fn test_take_and_restore_ids() {
+        let mut breakpoints = Breakpoints::default();
+        for (line_no, id) in [(10, "1"), (12, "2")] {
+            let mut breakpoint = Breakpoint::line("file:///foo.php".to_string(), line_no);
+            breakpoint.id = Some(id.to_string());
+            breakpoints.add(breakpoint);
+        }
+
+        let ids = breakpoints.take_ids();
+        assert_eq!(2, ids.len());
+        assert_eq!(None, breakpoints.breakpoints[0].id);
+
+        breakpoints.remove_line("file:///foo.php", 12);
+        breakpoints.add(Breakpoint::line("file:///foo.php".to_string(), 14));
+        let stale = breakpoints.restore_ids(ids);
+
+        assert_eq!(vec!["2".to_string()], stale);
+        assert_eq!(Some("1".to_string()), breakpoints.breakpoints[0].id);
+        assert_eq!(None, breakpoints.breakpoints[1].id);
+    }
+
+    #[test]
+    fn test_parse_hit() {
+        assert_eq!(None, parse_hit("").unwrap());
+        assert_eq!(Some((HitCondition::GreaterOrEqual, 4000)), parse_hit("4000").unwrap());
+        assert_eq!(Some((HitCondition::GreaterOrEqual, 10)), parse_hit(">= 10").unwrap());
+        assert_eq!(Some((HitCondition::Equal, 10)), parse_hit("==10").unwrap());
+        assert_eq!(Some((HitCondition::Multiple, 2)), parse_hit(" % 2 ").unwrap());
+        assert!(parse_hit("<= 2").is_err());
+        assert!(parse_hit(">=").is_err());
+    }
+}
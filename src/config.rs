@@ -1,19 +1,256 @@
+use crate::dbgp::client::OutputMode;
+use crate::keymap::Keymap;
+use crate::step_filter::StepFilters;
+use crate::theme::Theme;
+use crate::view::Col;
+use crate::view::ComponentType;
+use crate::view::Pane;
+use crate::workspace::PathMapping;
+use anyhow::Result;
 use clap::Parser;
+use ratatui::layout::Constraint;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long)]
     pub listen: Option<String>,
     #[arg(long)]
     pub log: Option<String>,
+    /// register with the DBGp proxy at host:port
+    #[arg(long)]
+    pub proxy: Option<String>,
+    /// IDE key to register with the DBGp proxy
+    #[arg(long)]
+    pub idekey: Option<String>,
 }
 
-pub fn load_config() -> Config {
+/// load the configuration from the user configuration file, the project
+/// configuration file and the CLI arguments (in order of precedence)
+pub fn load_config() -> Result<Config> {
     let args = Args::parse();
-    Config {
-        listen: args.listen.unwrap_or("0.0.0.0:9003".to_string()),
-        log_path: args.log,
+    let cwd = std::env::current_dir().unwrap_or(PathBuf::from("."));
+    let project_dir = find_project_dir(&cwd).unwrap_or(cwd.join(PROJECT_DIR));
+
+    let mut file = ConfigFile::default();
+    for path in [user_config_path(), Some(project_dir.join(CONFIG_FILE))]
+        .into_iter()
+        .flatten()
+    {
+        file = file.merge(ConfigFile::load(&path)?);
+    }
+
+    build_config(args, file, project_dir)
+}
+
+fn build_config(args: Args, file: ConfigFile, project_dir: PathBuf) -> Result<Config> {
+    let mut config = Config::new(args.listen.or(file.listen).unwrap_or(DEFAULT_LISTEN.to_string()));
+    config.log_path = args.log;
+    config.project_dir = project_dir;
+    config.proxy = args.proxy.or(file.proxy);
+    config.idekey = args.idekey.or(file.idekey);
+    if config.proxy.is_some() && config.idekey.is_none() {
+        anyhow::bail!("An IDE key (--idekey) is required to register with the DBGp proxy");
+    }
+    if let Some(output) = file.output {
+        config.output = output;
+    }
+    if let Some(step_filters) = file.step_filters {
+        config.step_filters = StepFilters::new(step_filters);
+    }
+    if let Some(theme) = file.theme {
+        config.theme = theme;
+    }
+    if let Some(context_depth) = file.context_depth {
+        config.context_depth = context_depth.clamp(1, 9);
+    }
+    if let Some(stack_max_context_fetch) = file.stack_max_context_fetch {
+        config.stack_max_context_fetch = stack_max_context_fetch;
+    }
+    if let Some(panes) = file.panes {
+        if panes.is_empty() {
+            anyhow::bail!("At least one pane must be configured");
+        }
+        config.panes = Some(
+            panes
+                .iter()
+                .map(|p| p.to_pane())
+                .collect::<Result<Vec<Pane>>>()?,
+        );
+    }
+    if let Some(path_mappings) = file.path_mappings {
+        // relative local paths are relative to the project root
+        let project_root = config.project_dir.parent().unwrap_or(Path::new(".")).to_path_buf();
+        config.path_mappings = path_mappings
+            .into_iter()
+            .map(|mapping| PathMapping {
+                local: project_root
+                    .join(&mapping.local)
+                    .components()
+                    .collect::<PathBuf>()
+                    .to_string_lossy()
+                    .to_string(),
+                remote: mapping.remote,
+            })
+            .collect();
+    }
+    if let Some(keys) = file.keys {
+        let mut bindings = HashMap::new();
+        flatten_keys("", keys, &mut bindings);
+        config.keymap = Keymap::with_overrides(&bindings)?;
+    }
+    Ok(config)
+}
+
+pub const PROJECT_DIR: &str = ".debug-tui";
+const CONFIG_FILE: &str = "config.toml";
+const DEFAULT_LISTEN: &str = "0.0.0.0:9003";
+
+/// find the nearest `.debug-tui` directory in the given directory or any of
+/// its ancestors
+pub fn find_project_dir(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_DIR))
+        .find(|dir| dir.is_dir())
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("debug-tui").join(CONFIG_FILE))
+}
+
+/// contents of a `config.toml` file, all values are optional
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    listen: Option<String>,
+    theme: Option<Theme>,
+    context_depth: Option<u16>,
+    stack_max_context_fetch: Option<u16>,
+    panes: Option<Vec<PaneConfig>>,
+    keys: Option<HashMap<String, Keys>>,
+    path_mappings: Option<Vec<PathMapping>>,
+    proxy: Option<String>,
+    idekey: Option<String>,
+    output: Option<OutputMode>,
+    step_filters: Option<Vec<String>>,
+}
+
+/// one or more keys bound to an action. Dotted action names such as
+/// `history.previous` are parsed by TOML as nested tables.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+    Nested(HashMap<String, Keys>),
+}
+
+fn flatten_keys(prefix: &str, keys: HashMap<String, Keys>, bindings: &mut HashMap<String, Vec<String>>) {
+    for (name, keys) in keys {
+        let name = match prefix {
+            "" => name,
+            prefix => format!("{}.{}", prefix, name),
+        };
+        match keys {
+            Keys::One(key) => {
+                bindings.insert(name, vec![key]);
+            }
+            Keys::Many(keys) => {
+                bindings.insert(name, keys);
+            }
+            Keys::Nested(keys) => flatten_keys(&name, keys, bindings),
+        }
+    }
+}
+
+impl ConfigFile {
+    fn load(path: &Path) -> Result<ConfigFile> {
+        if !path.exists() {
+            return Ok(ConfigFile::default());
+        }
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| anyhow::anyhow!("Could not parse {}: {}", path.display(), e))
+    }
+
+    fn parse(contents: &str) -> Result<ConfigFile> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    // values in the other file take precedence
+    fn merge(self, other: ConfigFile) -> ConfigFile {
+        ConfigFile {
+            listen: other.listen.or(self.listen),
+            theme: other.theme.or(self.theme),
+            context_depth: other.context_depth.or(self.context_depth),
+            stack_max_context_fetch: other.stack_max_context_fetch.or(self.stack_max_context_fetch),
+            panes: other.panes.or(self.panes),
+            path_mappings: other.path_mappings.or(self.path_mappings),
+            proxy: other.proxy.or(self.proxy),
+            idekey: other.idekey.or(self.idekey),
+            output: other.output.or(self.output),
+            step_filters: other.step_filters.or(self.step_filters),
+            keys: match (self.keys, other.keys) {
+                (Some(keys), Some(other_keys)) => {
+                    let mut bindings = HashMap::new();
+                    flatten_keys("", keys, &mut bindings);
+                    flatten_keys("", other_keys, &mut bindings);
+                    Some(
+                        bindings
+                            .into_iter()
+                            .map(|(name, keys)| (name, Keys::Many(keys)))
+                            .collect(),
+                    )
+                }
+                (keys, other_keys) => other_keys.or(keys),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct PaneConfig {
+    component: ComponentType,
+    col: Col,
+    #[serde(default = "PaneConfig::default_size")]
+    size: String,
+}
+
+impl PaneConfig {
+    fn default_size() -> String {
+        "fill".to_string()
+    }
+
+    fn to_pane(&self) -> Result<Pane> {
+        Ok(Pane {
+            component_type: self.component.clone(),
+            col: self.col.clone(),
+            constraint: parse_constraint(&self.size)?,
+        })
+    }
+}
+
+/// parse a pane size: `75%` (percentage), `10` (lines or columns) or `fill`
+fn parse_constraint(size: &str) -> Result<Constraint> {
+    let size = size.trim();
+    if size == "fill" {
+        return Ok(Constraint::Fill(1));
+    }
+    if let Some(percentage) = size.strip_suffix('%') {
+        return Ok(Constraint::Percentage(percentage.trim().parse()?));
+    }
+    match size.parse::<u16>() {
+        Ok(length) => Ok(Constraint::Length(length)),
+        Err(_) => anyhow::bail!("Invalid pane size \"{}\", expected e.g. \"75%\", \"10\" or \"fill\"", size),
     }
 }
 
@@ -21,27 +258,184 @@ pub fn load_config() -> Config {
 pub struct Config {
     pub listen: String,
     pub log_path: Option<String>,
+    pub project_dir: PathBuf,
+    pub theme: Theme,
+    pub context_depth: u16,
+    pub stack_max_context_fetch: u16,
+    pub panes: Option<Vec<Pane>>,
+    pub keymap: Keymap,
+    pub path_mappings: Vec<PathMapping>,
+    pub proxy: Option<String>,
+    pub idekey: Option<String>,
+    pub output: OutputMode,
+    /// files which are skipped when stepping
+    pub step_filters: StepFilters,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self::new("0.0.0.0:9003".to_string())
+        Self::new(DEFAULT_LISTEN.to_string())
     }
 }
 
 impl Config {
     pub fn new(listen: String) -> Config {
-        Config { listen , log_path: None}
+        Config {
+            listen,
+            log_path: None,
+            project_dir: PathBuf::from(PROJECT_DIR),
+            theme: Theme::SolarizedDark,
+            context_depth: 4,
+            stack_max_context_fetch: 1,
+            panes: None,
+            keymap: Keymap::default(),
+            path_mappings: vec![],
+            proxy: None,
+            idekey: None,
+            output: OutputMode::default(),
+            step_filters: StepFilters::default(),
+        }
+    }
+
+    /// the port we listen on, which is registered with the DBGp proxy
+    pub fn listen_port(&self) -> Result<u16> {
+        match self.listen.rsplit_once(':') {
+            Some((_, port)) => Ok(port.parse()?),
+            None => anyhow::bail!("Listen address \"{}\" does not have a port", self.listen),
+        }
+    }
+
+    pub fn state_path(&self) -> PathBuf {
+        self.project_dir.join("state.toml")
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+    use crate::keymap::Action;
     use crate::notification::Notification;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn test_countdown_char() {
         let notification = Notification::info("Hello".to_string());
         notification.countdown_char();
     }
+
+    #[test]
+    fn test_find_project_dir() -> Result<(), anyhow::Error> {
+        let root = std::env::temp_dir().join(format!("debug-tui-config-{}", std::process::id()));
+        let nested = root.join("src").join("Foo");
+        std::fs::create_dir_all(&nested)?;
+
+        assert_eq!(None, find_project_dir(&nested).filter(|d| d.starts_with(&root)));
+
+        std::fs::create_dir_all(root.join(PROJECT_DIR))?;
+        assert_eq!(Some(root.join(PROJECT_DIR)), find_project_dir(&nested));
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_precedence() -> Result<(), anyhow::Error> {
+        let user = ConfigFile::parse(
+            r#"
+            listen = "0.0.0.0:9000"
+            idekey = "dan"
+            theme = "dark"
+            context_depth = 2
+
+            [keys]
+            step_into = "s"
+            step_over = "S"
+            "history.next" = "m"
+            "#,
+        )?;
+        let project = ConfigFile::parse(
+            r#"
+            context_depth = 6
+            stack_max_context_fetch = 3
+            output = "redirect"
+            step_filters = ["*/vendor/*"]
+
+            [keys]
+            step_over = ["N", "f"]
+            history.previous = "P"
+            "#,
+        )?;
+        let args = Args {
+            listen: Some("127.0.0.1:9003".to_string()),
+            proxy: Some("proxy:9001".to_string()),
+            ..Default::default()
+        };
+
+        let config = build_config(args, user.merge(project), PathBuf::from("/app"))?;
+        assert_eq!("127.0.0.1:9003", config.listen);
+        assert_eq!(9003, config.listen_port()?);
+        assert_eq!(Some("proxy:9001".to_string()), config.proxy);
+        assert_eq!(Some("dan".to_string()), config.idekey);
+
+        let args = Args {
+            proxy: Some("proxy:9001".to_string()),
+            ..Default::default()
+        };
+        assert!(build_config(args, ConfigFile::default(), PathBuf::from("/app")).is_err());
+        assert_eq!(Theme::Dark, config.theme);
+        assert_eq!(6, config.context_depth);
+        assert_eq!(3, config.stack_max_context_fetch);
+        assert_eq!(OutputMode::Redirect, config.output);
+        assert_eq!(vec!["*/vendor/*".to_string()], config.step_filters.patterns);
+        assert_eq!(PathBuf::from("/app/state.toml"), config.state_path());
+        assert_eq!("s", config.keymap.keys(Action::StepInto));
+        assert_eq!("N, f", config.keymap.keys(Action::StepOver));
+        assert_eq!("P", config.keymap.keys(Action::HistoryPrevious));
+        assert_eq!("m", config.keymap.keys(Action::HistoryNext));
+        assert!(build_config(
+            Args::default(),
+            ConfigFile::parse("[keys]\nstep_into = \"hyper-s\"")?,
+            PathBuf::from("/app")
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_panes() -> Result<(), anyhow::Error> {
+        let file = ConfigFile::parse(
+            r#"
+            [[panes]]
+            component = "source"
+            col = "left"
+            size = "70%"
+
+            [[panes]]
+            component = "context"
+            col = "right"
+
+            [[path_mappings]]
+            remote = "/var/www/html"
+            local = "."
+
+            [[path_mappings]]
+            remote = "/opt/lib"
+            local = "/home/me/lib"
+            "#,
+        )?;
+        let config = build_config(Args::default(), file, PathBuf::from("/app/.debug-tui"))?;
+        let panes = config.panes.unwrap();
+        assert_eq!(2, panes.len());
+        assert_eq!(ComponentType::Source, panes[0].component_type);
+        assert_eq!(Constraint::Percentage(70), panes[0].constraint);
+        assert_eq!(Col::Right, panes[1].col);
+        assert_eq!(Constraint::Fill(1), panes[1].constraint);
+        assert_eq!("/app", config.path_mappings[0].local);
+        assert_eq!("/home/me/lib", config.path_mappings[1].local);
+
+        assert!(ConfigFile::parse("foo = 1").is_err());
+        assert!(parse_constraint("ten").is_err());
+        assert_eq!(Constraint::Length(10), parse_constraint("10")?);
+        Ok(())
+    }
 }
@@ -3,17 +3,30 @@ use base64::engine::general_purpose;
 use base64::Engine;
 use core::str;
 use log::debug;
+use log::warn;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
+use tokio::net::tcp::OwnedReadHalf;
+use tokio::net::tcp::OwnedWriteHalf;
 use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use xmltree::Element;
 use xmltree::XMLNode;
 
 #[derive(Debug, Clone)]
 pub struct Init {
     pub fileuri: String,
+    pub idekey: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -27,11 +40,23 @@ pub enum CommandResponse {
     StepInto(ContinuationResponse),
     StepOver(ContinuationResponse),
     Run(ContinuationResponse),
+    Stop(ContinuationResponse),
+    Detach(ContinuationResponse),
     Unknown,
     StackGet(StackGetResponse),
     Source(String),
+    ContextNames(Vec<ContextName>),
     ContextGet(ContextGetResponse),
+    PropertyGet(Property),
+    PropertySet(bool),
     Eval(EvalResponse),
+    BreakpointSet(BreakpointSetResponse),
+    BreakpointGet(Breakpoint),
+    BreakpointRemove,
+    BreakpointUpdate,
+    BreakpointList(Vec<Breakpoint>),
+    Redirect(bool),
+    Break(bool),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,6 +65,23 @@ pub struct DbgpError {
     pub code: String,
 }
 
+/// a context (e.g. locals or superglobals) offered by the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextName {
+    pub id: u32,
+    pub name: String,
+}
+
+impl ContextName {
+    /// the locals context, which every engine has
+    pub fn locals() -> Self {
+        ContextName {
+            id: 0,
+            name: "Locals".to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ContextGetResponse {
     pub properties: Properties,
@@ -71,12 +113,38 @@ impl Properties {
         self.defined_properties().into_iter().find(|&property| property.name == name).map(|v| v as _)
     }
 
+    /// find a property at any level by its fullname
+    pub(crate) fn find(&self, fullname: &str) -> Option<&Property> {
+        for property in self.defined_properties() {
+            if property.fullname == fullname {
+                return Some(property);
+            }
+            if let Some(property) = property.children.find(fullname) {
+                return Some(property);
+            }
+        }
+        None
+    }
+
+    /// find a property at any level by its fullname
+    pub(crate) fn find_mut(&mut self, fullname: &str) -> Option<&mut Property> {
+        for property in self.properties.iter_mut() {
+            if property.fullname == fullname {
+                return Some(property);
+            }
+            if let Some(property) = property.children.find_mut(fullname) {
+                return Some(property);
+            }
+        }
+        None
+    }
+
     pub fn from_properties(vec: Vec<Property>) -> Properties {
         Self { properties: vec }
     }
 }
 
-#[derive(PartialEq, Clone, Debug, Default)]
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub enum PropertyType {
     Bool,
     Int,
@@ -98,6 +166,14 @@ impl Display for PropertyType {
 }
 
 impl PropertyType {
+    /// values of these types can be set with property_set
+    pub fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            PropertyType::Bool | PropertyType::Int | PropertyType::Float | PropertyType::String
+        )
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             PropertyType::Bool => "bool",
@@ -139,6 +215,8 @@ pub struct Property {
     pub property_type: PropertyType,
     pub facet: Option<String>,
     pub size: Option<u32>,
+    /// number of children, which can be more than have been fetched
+    pub numchildren: Option<u32>,
     pub children: Properties,
     pub key: Option<String>,
     pub address: Option<String>,
@@ -152,6 +230,30 @@ impl Property {
             _ => self.property_type.to_string(),
         }
     }
+    /// the value converted to a boolean as PHP would
+    pub(crate) fn is_truthy(&self) -> bool {
+        let value = self.value.as_deref().unwrap_or_default();
+        match self.property_type {
+            PropertyType::Bool | PropertyType::Int => !value.is_empty() && value != "0",
+            PropertyType::Float => value.parse::<f64>().is_ok_and(|f| f != 0.0),
+            PropertyType::String => !value.is_empty() && value != "0",
+            PropertyType::Array | PropertyType::Hash => self.has_children(),
+            PropertyType::Object | PropertyType::Resource => true,
+            PropertyType::Null | PropertyType::Undefined => false,
+        }
+    }
+
+    pub(crate) fn has_children(&self) -> bool {
+        !self.children.is_empty() || self.numchildren.is_some_and(|n| n > 0)
+    }
+
+    /// number of children which have not been fetched yet
+    pub(crate) fn remaining_children(&self) -> u32 {
+        self.numchildren
+            .unwrap_or_default()
+            .saturating_sub(self.children.properties.len() as u32)
+    }
+
     pub(crate) fn value_is(&self, value: &str) -> bool {
         match &self.value {
             Some(v) => value == *v,
@@ -160,17 +262,172 @@ impl Property {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakpointType {
+    #[default]
+    Line,
+    Call,
+    Return,
+    Exception,
+    Conditional,
+    Watch,
+}
+
+impl BreakpointType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BreakpointType::Line => "line",
+            BreakpointType::Call => "call",
+            BreakpointType::Return => "return",
+            BreakpointType::Exception => "exception",
+            BreakpointType::Conditional => "conditional",
+            BreakpointType::Watch => "watch",
+        }
+    }
+
+    pub fn is_line(&self) -> bool {
+        matches!(self, BreakpointType::Line | BreakpointType::Conditional)
+    }
+
+    fn from_str(expect: &str) -> BreakpointType {
+        match expect {
+            "call" => Self::Call,
+            "return" => Self::Return,
+            "exception" => Self::Exception,
+            "conditional" => Self::Conditional,
+            "watch" => Self::Watch,
+            _ => Self::Line,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakpointState {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
+impl BreakpointState {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BreakpointState::Enabled => "enabled",
+            BreakpointState::Disabled => "disabled",
+        }
+    }
+
+    fn from_str(expect: &str) -> BreakpointState {
+        match expect {
+            "disabled" => Self::Disabled,
+            _ => Self::Enabled,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub enum HitCondition {
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "%")]
+    Multiple,
+}
+
+impl HitCondition {
+    pub fn as_str(&self) -> &str {
+        match self {
+            HitCondition::GreaterOrEqual => ">=",
+            HitCondition::Equal => "==",
+            HitCondition::Multiple => "%",
+        }
+    }
+
+    pub fn from_operator(expect: &str) -> Option<HitCondition> {
+        match expect {
+            ">=" => Some(Self::GreaterOrEqual),
+            "==" => Some(Self::Equal),
+            "%" => Some(Self::Multiple),
+            _ => None,
+        }
+    }
+}
+
+/// A breakpoint as understood by the debugging engine. The `id` is assigned
+/// by the engine when the breakpoint is set.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Breakpoint {
+    #[serde(skip)]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub breakpoint_type: BreakpointType,
+    pub state: BreakpointState,
+    pub filename: Option<String>,
+    pub line_no: Option<u32>,
+    /// function name (`foo`) or method name (`Foo::bar`) for call and return breakpoints
+    pub function: Option<String>,
+    pub exception: Option<String>,
+    pub expression: Option<String>,
+    pub hit_value: Option<u32>,
+    pub hit_condition: Option<HitCondition>,
+    #[serde(skip)]
+    pub hit_count: Option<u32>,
+    /// removed by the engine once it has been hit
+    #[serde(skip)]
+    pub temporary: bool,
+}
+
+impl Breakpoint {
+    pub fn line(filename: String, line_no: u32) -> Self {
+        Breakpoint {
+            breakpoint_type: BreakpointType::Line,
+            filename: Some(filename),
+            line_no: Some(line_no),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakpointSetResponse {
+    pub id: String,
+    pub state: BreakpointState,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum ContinuationStatus {
     Break,
     Stopping,
+    Stopped,
     Unknown(String),
 }
 
+impl Display for ContinuationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ContinuationStatus::Break => "break",
+            ContinuationStatus::Stopping => "stopping",
+            ContinuationStatus::Stopped => "stopped",
+            ContinuationStatus::Unknown(status) => status.as_str(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContinuationException {
+    pub class: String,
+    pub message: String,
+    pub code: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ContinuationResponse {
     pub status: ContinuationStatus,
     pub reason: String,
+    pub exception: Option<ContinuationException>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -204,88 +461,325 @@ pub struct StackEntry {
     pub filename: String,
     pub line: u32,
     pub level: u32,
+    /// the function or method of the frame, e.g. `Foo->bar` or `{main}`
+    pub function: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamType {
+    Stdout,
+    Stderr,
+}
+
+/// output of the script sent by the engine when stdout or stderr is
+/// redirected or copied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stream {
+    pub stream_type: StreamType,
+    pub data: String,
+}
+
+/// how the engine handles output of the script, see the DBGp `stdout` and
+/// `stderr` commands
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    Disable,
+    /// send output to the debugger and to the usual destination
+    #[default]
+    Copy,
+    /// send output only to the debugger
+    Redirect,
+}
+
+impl OutputMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            OutputMode::Disable => "0",
+            OutputMode::Copy => "1",
+            OutputMode::Redirect => "2",
+        }
+    }
+}
+
+/// a PHP error, warning or notice reported by the engine
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotifyError {
+    pub error_type: String,
+    pub message: String,
+    pub filename: Option<String>,
+    pub line_no: Option<u32>,
+}
+
+/// asynchronous notification sent by the engine when the `notify_ok`
+/// feature is enabled
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineNotify {
+    Error(NotifyError),
+    BreakpointResolved(Breakpoint),
+    Other(String),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Init(Init),
     Response(Response),
+    Stream(Stream),
+    Notify(EngineNotify),
+}
+
+type PendingResponses = Arc<StdMutex<HashMap<u32, oneshot::Sender<Result<Message>>>>>;
+
+/// a command which has been sent to the engine, the response is routed to
+/// it by the reader task
+pub struct Pending {
+    receiver: oneshot::Receiver<Result<Message>>,
+}
+
+impl Pending {
+    pub(crate) async fn response(self) -> Result<Message> {
+        match self.receiver.await {
+            Ok(message) => message,
+            Err(_) => anyhow::bail!("Connection was closed"),
+        }
+    }
+
+    /// wait for the response to `break`
+    pub(crate) async fn broken(self) -> Result<()> {
+        match self.response().await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::Break(true) => Ok(()),
+                CommandResponse::Break(false) => anyhow::bail!("Engine could not break"),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    /// wait for the response to `run` or one of the `step_*` commands
+    pub(crate) async fn continuation(self) -> Result<ContinuationResponse> {
+        match self.response().await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::Run(s)
+                | CommandResponse::StepInto(s)
+                | CommandResponse::StepOver(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
 }
 
 pub struct DbgpClient {
     tid: u32,
-    stream: Option<TcpStream>,
+    writer: Option<OwnedWriteHalf>,
+    reader: Option<JoinHandle<()>>,
+    pending: PendingResponses,
+    /// set by the reader task when the engine closes the connection
+    closed: Arc<AtomicBool>,
+    output: Arc<StdMutex<Vec<Stream>>>,
+    notifications: Arc<StdMutex<Vec<EngineNotify>>>,
 }
 
 impl DbgpClient {
     pub(crate) fn new(s: Option<TcpStream>) -> Self {
-        Self { stream: s, tid: 0 }
+        let mut client = Self {
+            tid: 0,
+            writer: None,
+            reader: None,
+            pending: Arc::new(StdMutex::new(HashMap::new())),
+            closed: Arc::new(AtomicBool::new(false)),
+            output: Arc::new(StdMutex::new(vec![])),
+            notifications: Arc::new(StdMutex::new(vec![])),
+        };
+        if let Some(s) = s {
+            let (reader, writer) = s.into_split();
+            client.writer = Some(writer);
+            client.spawn_reader(BufReader::new(reader));
+        }
+        client
     }
 
-    pub fn is_connected(&self) -> bool {
-        self.stream.is_some()
+    /// take the output received since the last call
+    pub fn take_output(&mut self) -> Vec<Stream> {
+        std::mem::take(&mut self.output.lock().unwrap())
     }
 
-    pub(crate) async fn read_and_parse(&mut self) -> Result<Message> {
-        let xml = self.read_raw().await?;
-        if xml.is_empty() {
-            return Err(anyhow::anyhow!("Empty XML response"));
-        }
-        parse_xml(xml.as_str())
+    /// take the notifications received since the last call
+    pub fn take_notifications(&mut self) -> Vec<EngineNotify> {
+        std::mem::take(&mut self.notifications.lock().unwrap())
     }
 
-    pub(crate) async fn read_raw(&mut self) -> Result<String> {
-        let mut length: Vec<u8> = Vec::new();
-        let mut xml: Vec<u8> = Vec::new();
-        let mut reader = BufReader::new(self.stream.as_mut().unwrap());
+    pub fn is_connected(&self) -> bool {
+        self.writer.is_some() && !self.closed.load(Ordering::SeqCst)
+    }
 
-        // read length and subsequently ignore it
-        reader.read_until(b'\0', &mut length).await?;
+    // read packets until the connection is closed, responses are routed to
+    // the pending command with the same transaction ID
+    fn spawn_reader(&mut self, mut reader: BufReader<OwnedReadHalf>) {
+        let pending = Arc::clone(&self.pending);
+        let closed = Arc::clone(&self.closed);
+        let output = Arc::clone(&self.output);
+        let notifications = Arc::clone(&self.notifications);
+        self.reader = Some(tokio::spawn(async move {
+            loop {
+                let xml = match read_packet(&mut reader).await {
+                    Ok(xml) if xml.is_empty() => break,
+                    Ok(xml) => xml,
+                    Err(e) => {
+                        warn!("could not read packet: {}", e);
+                        break;
+                    }
+                };
+                let (tid, message) = match parse_xml(&xml) {
+                    Ok(Message::Stream(stream)) => {
+                        output.lock().unwrap().push(stream);
+                        continue;
+                    }
+                    Ok(Message::Notify(notify)) => {
+                        notifications.lock().unwrap().push(notify);
+                        continue;
+                    }
+                    Ok(Message::Response(r)) => (r.transaction_id.parse().ok(), Ok(Message::Response(r))),
+                    Ok(message) => (None, Ok(message)),
+                    // the command still needs to be answered if the response
+                    // could not be parsed
+                    Err(e) => (transaction_id(&xml), Err(e)),
+                };
+                match tid.and_then(|tid| pending.lock().unwrap().remove(&tid)) {
+                    Some(sender) => {
+                        let _ = sender.send(message);
+                    }
+                    None => warn!("no command is waiting for response: {}", xml),
+                }
+            }
+            // flag the connection as closed while holding the lock so that no
+            // command can be registered after the senders are dropped
+            let mut pending = pending.lock().unwrap();
+            closed.store(true, Ordering::SeqCst);
+            // dropping the senders fails the outstanding commands
+            pending.clear();
+        }));
+    }
 
-        // read data
-        reader.read_until(b'\0', &mut xml).await?;
+    pub(crate) async fn run(&mut self) -> Result<Pending> {
+        self.send("run", &mut []).await
+    }
 
-        // remove dangling null-byte
-        if let Some(e) = xml.last() {
-            if *e == b'\0' {
-                xml.pop();
-            }
+    pub(crate) async fn feature_set(&mut self, feature: &str, value: &str) -> Result<()> {
+        match self
+            .command("feature_set", &mut ["-n", feature, "-v", value])
+            .await?
+        {
+            Message::Response(r) => match r.command {
+                CommandResponse::Unknown => Ok(()),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
         }
-        let string = String::from_utf8(xml)?;
-        debug!("[dbgp] << {}", string);
-        Ok(string)
     }
 
-    pub(crate) async fn run(&mut self) -> Result<ContinuationResponse> {
-        match self.command("run", &mut []).await? {
+    pub(crate) async fn context_names(&mut self, depth: u16) -> Result<Vec<ContextName>> {
+        match self
+            .command("context_names", &mut ["-d", format!("{}", depth).as_str()])
+            .await?
+        {
             Message::Response(r) => match r.command {
-                CommandResponse::Run(s) => Ok(s),
+                CommandResponse::ContextNames(s) => Ok(s),
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
         }
     }
 
-    pub(crate) async fn feature_set(&mut self, feature: &str, value: &str) -> Result<()> {
+    pub(crate) async fn context_get(
+        &mut self,
+        depth: u16,
+        context_id: u32,
+    ) -> Result<ContextGetResponse> {
         match self
-            .command("feature_set", &mut ["-n", feature, "-v", value])
+            .command(
+                "context_get",
+                &mut [
+                    "-d",
+                    format!("{}", depth).as_str(),
+                    "-c",
+                    format!("{}", context_id).as_str(),
+                ],
+            )
             .await?
         {
             Message::Response(r) => match r.command {
-                CommandResponse::Unknown => Ok(()),
+                CommandResponse::ContextGet(s) => Ok(s),
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
         }
     }
 
-    pub(crate) async fn context_get(&mut self, depth: u16) -> Result<ContextGetResponse> {
+    /// fetch a page of the children of a property
+    pub(crate) async fn property_get(
+        &mut self,
+        fullname: &str,
+        page: u32,
+        depth: u16,
+        context_id: u32,
+    ) -> Result<Property> {
+        let depth = depth.to_string();
+        let context_id = context_id.to_string();
+        let page = page.to_string();
+        let name = quote(fullname);
         match self
-            .command("context_get", &mut ["-d", format!("{}", depth).as_str()])
+            .command(
+                "property_get",
+                &mut ["-d", &depth, "-c", &context_id, "-p", &page, "-n", &name],
+            )
             .await?
         {
             Message::Response(r) => match r.command {
-                CommandResponse::ContextGet(s) => Ok(s),
+                CommandResponse::PropertyGet(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    /// set the value of a scalar property, the type is a hint for the engine
+    pub(crate) async fn property_set(
+        &mut self,
+        fullname: &str,
+        depth: u16,
+        context_id: u32,
+        property_type: PropertyType,
+        value: &str,
+    ) -> Result<()> {
+        let depth = depth.to_string();
+        let context_id = context_id.to_string();
+        let name = quote(fullname);
+        let base64 = general_purpose::STANDARD.encode(value.as_bytes());
+        match self
+            .command(
+                "property_set",
+                &mut [
+                    "-d",
+                    &depth,
+                    "-c",
+                    &context_id,
+                    "-t",
+                    property_type.as_str(),
+                    "-n",
+                    &name,
+                    "--",
+                    &base64,
+                ],
+            )
+            .await?
+        {
+            Message::Response(r) => match r.command {
+                CommandResponse::PropertySet(true) => Ok(()),
+                CommandResponse::PropertySet(false) => {
+                    anyhow::bail!("Engine could not set {}", fullname)
+                }
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
@@ -309,36 +803,147 @@ impl DbgpClient {
         }
     }
 
-    pub(crate) async fn step_into(&mut self) -> Result<ContinuationResponse> {
-        match self.command("step_into", &mut []).await? {
+    pub(crate) async fn breakpoint_set(
+        &mut self,
+        breakpoint: &Breakpoint,
+    ) -> Result<BreakpointSetResponse> {
+        let line_no = breakpoint.line_no.map(|l| l.to_string());
+        let hit_value = breakpoint.hit_value.map(|h| h.to_string());
+        let (class, function) = match breakpoint.function.as_ref().map(|f| f.split_once("::")) {
+            Some(Some((class, method))) => (Some(class), Some(method)),
+            Some(None) => (None, breakpoint.function.as_deref()),
+            None => (None, None),
+        };
+        let expression = breakpoint
+            .expression
+            .as_ref()
+            .map(|e| general_purpose::STANDARD.encode(e.as_bytes()));
+        let mut args = vec![
+            "-t",
+            breakpoint.breakpoint_type.as_str(),
+            "-s",
+            breakpoint.state.as_str(),
+        ];
+        if let Some(filename) = &breakpoint.filename {
+            args.extend(["-f", filename.as_str()]);
+        }
+        if let Some(line_no) = &line_no {
+            args.extend(["-n", line_no.as_str()]);
+        }
+        if let Some(function) = function {
+            args.extend(["-m", function]);
+        }
+        if let Some(class) = class {
+            args.extend(["-a", class]);
+        }
+        if let Some(exception) = &breakpoint.exception {
+            args.extend(["-x", exception.as_str()]);
+        }
+        if let (Some(hit_value), Some(hit_condition)) = (&hit_value, &breakpoint.hit_condition) {
+            args.extend(["-h", hit_value.as_str(), "-o", hit_condition.as_str()]);
+        }
+        if breakpoint.temporary {
+            args.extend(["-r", "1"]);
+        }
+        if let Some(expression) = &expression {
+            args.extend(["--", expression.as_str()]);
+        }
+        match self.command("breakpoint_set", &mut args).await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::BreakpointSet(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    pub(crate) async fn breakpoint_get(&mut self, id: &str) -> Result<Breakpoint> {
+        match self.command("breakpoint_get", &mut ["-d", id]).await? {
             Message::Response(r) => match r.command {
-                CommandResponse::StepInto(s) => Ok(s),
+                CommandResponse::BreakpointGet(s) => Ok(s),
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
         }
     }
 
-    pub(crate) async fn step_out(&mut self) -> Result<ContinuationResponse> {
-        match self.command("step_out", &mut []).await? {
+    pub(crate) async fn breakpoint_update(
+        &mut self,
+        id: &str,
+        state: &BreakpointState,
+    ) -> Result<()> {
+        match self
+            .command("breakpoint_update", &mut ["-d", id, "-s", state.as_str()])
+            .await?
+        {
             Message::Response(r) => match r.command {
-                CommandResponse::StepInto(s) => Ok(s),
+                CommandResponse::BreakpointUpdate => Ok(()),
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
         }
     }
 
-    pub(crate) async fn step_over(&mut self) -> Result<ContinuationResponse> {
-        match self.command("step_over", &mut []).await? {
+    pub(crate) async fn breakpoint_remove(&mut self, id: &str) -> Result<()> {
+        match self.command("breakpoint_remove", &mut ["-d", id]).await? {
             Message::Response(r) => match r.command {
-                CommandResponse::StepOver(s) => Ok(s),
+                CommandResponse::BreakpointRemove => Ok(()),
                 _ => anyhow::bail!("Unexpected response"),
             },
             _ => anyhow::bail!("Unexpected response"),
         }
     }
 
+    pub(crate) async fn breakpoint_list(&mut self) -> Result<Vec<Breakpoint>> {
+        match self.command("breakpoint_list", &mut []).await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::BreakpointList(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    /// end the debugging session and abort the script
+    pub(crate) async fn stop(&mut self) -> Result<ContinuationResponse> {
+        match self.command("stop", &mut []).await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::Stop(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    /// end the debugging session and let the script run to completion
+    pub(crate) async fn detach(&mut self) -> Result<ContinuationResponse> {
+        match self.command("detach", &mut []).await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::Detach(s) => Ok(s),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
+    /// interrupt the engine while it is executing a continuation command
+    /// the engine only answers once the script has been interrupted
+    pub(crate) async fn break_execution(&mut self) -> Result<Pending> {
+        self.send("break", &mut []).await
+    }
+
+    pub(crate) async fn step_into(&mut self) -> Result<Pending> {
+        self.send("step_into", &mut []).await
+    }
+
+    pub(crate) async fn step_out(&mut self) -> Result<Pending> {
+        self.send("step_out", &mut []).await
+    }
+
+    pub(crate) async fn step_over(&mut self) -> Result<Pending> {
+        self.send("step_over", &mut []).await
+    }
+
     pub(crate) async fn get_stack(&mut self) -> Result<StackGetResponse> {
         match self.command("stack_get", &mut ["-n 0"]).await? {
             Message::Response(r) => match r.command {
@@ -362,41 +967,119 @@ impl DbgpClient {
         }
     }
 
+    pub(crate) async fn stdout(&mut self, mode: OutputMode) -> Result<()> {
+        self.redirect("stdout", mode).await
+    }
+
+    pub(crate) async fn stderr(&mut self, mode: OutputMode) -> Result<()> {
+        self.redirect("stderr", mode).await
+    }
+
+    async fn redirect(&mut self, cmd: &str, mode: OutputMode) -> Result<()> {
+        match self.command(cmd, &mut ["-c", mode.as_str()]).await? {
+            Message::Response(r) => match r.command {
+                CommandResponse::Redirect(true) => Ok(()),
+                CommandResponse::Redirect(false) => anyhow::bail!("Engine does not support {}", cmd),
+                _ => anyhow::bail!("Unexpected response"),
+            },
+            _ => anyhow::bail!("Unexpected response"),
+        }
+    }
+
     async fn command(&mut self, cmd: &str, args: &mut [&str]) -> Result<Message> {
-        self.command_raw(cmd, args).await?;
-        self.read_and_parse().await
+        self.send(cmd, args).await?.response().await
     }
 
-    async fn command_raw(&mut self, cmd: &str, args: &mut [&str]) -> Result<usize> {
-        let cmd_str = format!("{} -i {} {}", cmd, self.tid, args.join(" "));
+    /// send a command without waiting for the response, the client does not
+    /// need to be borrowed while the engine is processing it
+    pub(crate) async fn send(&mut self, cmd: &str, args: &mut [&str]) -> Result<Pending> {
+        let tid = self.tid;
+        let cmd_str = format!("{} -i {} {}", cmd, tid, args.join(" "));
         debug!("[dbgp] >> {}", cmd_str);
         let bytes = [cmd_str.trim_end(), "\0"].concat();
         self.tid += 1;
-        match self.stream.as_mut() {
-            Some(stream) => stream
-                .write(bytes.as_bytes())
-                .await
-                .map_err(anyhow::Error::from),
+
+        // register before writing as the response could otherwise arrive first
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if self.closed.load(Ordering::SeqCst) {
+                anyhow::bail!("Stream was closed");
+            }
+            pending.insert(tid, sender);
+        }
+        let result = match self.writer.as_mut() {
+            Some(writer) => writer.write_all(bytes.as_bytes()).await.map_err(anyhow::Error::from),
             None => Err(anyhow::anyhow!("Stream was closed")),
+        };
+        if let Err(e) = result {
+            self.pending.lock().unwrap().remove(&tid);
+            return Err(e);
         }
+        Ok(Pending { receiver })
     }
 
     pub(crate) async fn disonnect(&mut self) -> Result<(), anyhow::Error> {
-        if let Some(s) = &mut self.stream {
-            let res = s.shutdown().await.or_else(|e| anyhow::bail!(e.to_string()));
-            self.stream = None;
-            return res;
+        if let Some(reader) = self.reader.take() {
+            reader.abort();
+        }
+        self.pending.lock().unwrap().clear();
+        if let Some(mut writer) = self.writer.take() {
+            return writer.shutdown().await.or_else(|e| anyhow::bail!(e.to_string()));
         };
         Ok(())
     }
 
     pub(crate) async fn connect(&mut self, s: TcpStream) -> Result<Init> {
-        self.stream = Some(s);
-        match self.read_and_parse().await? {
-            crate::dbgp::client::Message::Init(i) => Ok(i),
+        let (reader, writer) = s.into_split();
+        let mut reader = BufReader::new(reader);
+        // the engine sends the init packet before accepting commands
+        let init = match parse_xml(&read_packet(&mut reader).await?)? {
+            Message::Init(i) => i,
             _ => anyhow::bail!("Unexpected response"),
+        };
+        self.writer = Some(writer);
+        self.closed = Arc::new(AtomicBool::new(false));
+        self.spawn_reader(reader);
+        Ok(init)
+    }
+}
+
+/// read a packet (`length\0xml\0`), an empty string is returned when the
+/// connection has been closed
+async fn read_packet(reader: &mut BufReader<OwnedReadHalf>) -> Result<String> {
+    let mut length: Vec<u8> = Vec::new();
+    let mut xml: Vec<u8> = Vec::new();
+
+    // read length and subsequently ignore it
+    reader.read_until(b'\0', &mut length).await?;
+
+    // read data
+    reader.read_until(b'\0', &mut xml).await?;
+
+    // remove dangling null-byte
+    if let Some(e) = xml.last() {
+        if *e == b'\0' {
+            xml.pop();
         }
     }
+    let string = String::from_utf8(xml)?;
+    debug!("[dbgp] << {}", string);
+    Ok(string)
+}
+
+/// quote an argument which can contain spaces or quotes, e.g. `$foo["a b"]`
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn transaction_id(xml: &str) -> Option<u32> {
+    Element::parse(xml.as_bytes())
+        .ok()?
+        .attributes
+        .get("transaction_id")?
+        .parse()
+        .ok()
 }
 
 fn parse_xml(xml: &str) -> Result<Message, anyhow::Error> {
@@ -408,6 +1091,7 @@ fn parse_xml(xml: &str) -> Result<Message, anyhow::Error> {
                 .get("fileuri")
                 .expect("Expected fileuri to be set")
                 .to_string(),
+            idekey: root.attributes.get("idekey").cloned(),
         })),
         "response" => Ok(Message::Response(Response {
             transaction_id: root
@@ -422,25 +1106,89 @@ fn parse_xml(xml: &str) -> Result<Message, anyhow::Error> {
                 .as_str()
             {
                 "step_into" => {
-                    CommandResponse::StepInto(parse_continuation_response(&root.attributes))
+                    CommandResponse::StepInto(parse_continuation_response(&root))
                 }
                 "step_out" => {
-                    CommandResponse::StepInto(parse_continuation_response(&root.attributes))
+                    CommandResponse::StepInto(parse_continuation_response(&root))
                 }
                 "step_over" => {
-                    CommandResponse::StepOver(parse_continuation_response(&root.attributes))
+                    CommandResponse::StepOver(parse_continuation_response(&root))
                 }
-                "run" => CommandResponse::Run(parse_continuation_response(&root.attributes)),
+                "run" => CommandResponse::Run(parse_continuation_response(&root)),
+                "stop" => CommandResponse::Stop(parse_continuation_response(&root)),
+                "detach" => CommandResponse::Detach(parse_continuation_response(&root)),
                 "stack_get" => CommandResponse::StackGet(parse_stack_get(&root)),
                 "source" => CommandResponse::Source(parse_source(&root)?),
+                "context_names" => CommandResponse::ContextNames(parse_context_names(&root)),
                 "context_get" => CommandResponse::ContextGet(parse_context_get(&mut root)?),
                 "eval" => CommandResponse::Eval(parse_eval(&mut root)?),
+                "property_get" => CommandResponse::PropertyGet(parse_property_get(&mut root)?),
+                "property_set" => CommandResponse::PropertySet(
+                    root.attributes.get("success").is_some_and(|s| s == "1"),
+                ),
+                "breakpoint_set" => CommandResponse::BreakpointSet(parse_breakpoint_set(&root)?),
+                "breakpoint_get" => match parse_breakpoints(&root).pop() {
+                    Some(breakpoint) => CommandResponse::BreakpointGet(breakpoint),
+                    None => anyhow::bail!("Expected breakpoint element"),
+                },
+                "breakpoint_remove" => CommandResponse::BreakpointRemove,
+                "breakpoint_update" => CommandResponse::BreakpointUpdate,
+                "breakpoint_list" => CommandResponse::BreakpointList(parse_breakpoints(&root)),
+                "break" => CommandResponse::Break(
+                    root.attributes.get("success").is_some_and(|s| s == "1"),
+                ),
+                "stdout" | "stderr" => CommandResponse::Redirect(
+                    root.attributes.get("success").is_some_and(|s| s == "1"),
+                ),
                 _ => CommandResponse::Unknown,
             },
         })),
+        "stream" => Ok(Message::Stream(parse_stream(&root)?)),
+        "notify" => Ok(Message::Notify(parse_notify(&root))),
         _ => anyhow::bail!("Unexpected element: {}", root.name),
     }
 }
+
+fn parse_notify(element: &Element) -> EngineNotify {
+    let name = element.attributes.get("name").cloned().unwrap_or_default();
+    match name.as_str() {
+        "error" => match element.get_child("message") {
+            Some(message) => EngineNotify::Error(NotifyError {
+                error_type: message.attributes.get("type").cloned().unwrap_or_default(),
+                message: decode_element(Some(message)).unwrap_or_default(),
+                filename: message.attributes.get("filename").cloned(),
+                line_no: message.attributes.get("lineno").and_then(|l| l.parse().ok()),
+            }),
+            None => EngineNotify::Other(name),
+        },
+        "breakpoint_resolved" => match parse_breakpoints(element).pop() {
+            Some(breakpoint) => EngineNotify::BreakpointResolved(breakpoint),
+            None => EngineNotify::Other(name),
+        },
+        _ => EngineNotify::Other(name),
+    }
+}
+
+fn parse_stream(element: &Element) -> Result<Stream, anyhow::Error> {
+    let stream_type = match element.attributes.get("type").map(|t| t.as_str()) {
+        Some("stdout") => StreamType::Stdout,
+        Some("stderr") => StreamType::Stderr,
+        t => anyhow::bail!("Unexpected stream type: {:?}", t),
+    };
+    let data = match element.children.first() {
+        Some(XMLNode::CData(e)) | Some(XMLNode::Text(e)) => {
+            match element.attributes.get("encoding").map(|e| e.as_str()) {
+                Some("base64") => {
+                    String::from_utf8_lossy(&general_purpose::STANDARD.decode(e.trim())?).to_string()
+                }
+                _ => e.to_string(),
+            }
+        }
+        _ => String::new(),
+    };
+    Ok(Stream { stream_type, data })
+}
+
 fn parse_source(element: &Element) -> Result<String, anyhow::Error> {
     match element.children.first() {
         Some(XMLNode::CData(e)) => Ok(String::from_utf8(general_purpose::STANDARD.decode(e)?)?),
@@ -448,12 +1196,39 @@ fn parse_source(element: &Element) -> Result<String, anyhow::Error> {
     }
 }
 
+fn parse_context_names(element: &Element) -> Vec<ContextName> {
+    element
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            XMLNode::Element(e) if e.name == "context" => Some(ContextName {
+                id: e.attributes.get("id")?.parse().ok()?,
+                name: e.attributes.get("name").cloned().unwrap_or_default(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
 fn parse_context_get(element: &mut Element) -> Result<ContextGetResponse, anyhow::Error> {
     Ok(ContextGetResponse {
         properties: Properties::from_properties(parse_properties(element)?),
     })
 }
 
+fn parse_property_get(element: &mut Element) -> Result<Property, anyhow::Error> {
+    if let Some(error) = element.get_child("error") {
+        anyhow::bail!(
+            "Could not get property: {}",
+            decode_element(error.get_child("message")).unwrap_or_default()
+        );
+    }
+    match parse_properties(element)?.pop() {
+        Some(property) => Ok(property),
+        None => anyhow::bail!("Expected property element"),
+    }
+}
+
 fn parse_eval(element: &mut Element) -> Result<EvalResponse, anyhow::Error> {
     let error = if let Some(mut error_el) = element.take_child("error") {
         let code = error_el
@@ -519,6 +1294,10 @@ fn parse_properties(element: &mut Element) -> Result<Vec<Property>, anyhow::Erro
                 .attributes
                 .get("size")
                 .map(|s| s.parse::<u32>().unwrap()),
+            numchildren: child
+                .attributes
+                .get("numchildren")
+                .and_then(|s| s.parse::<u32>().ok()),
             key: child.attributes.get("key").map(|name| name.to_string()),
             address: child.attributes.get("address").map(|name| name.to_string()),
             encoding: encoding.clone(),
@@ -555,6 +1334,80 @@ fn decode_element(element: Option<&Element>) -> Option<String> {
     }
 }
 
+fn parse_breakpoint_set(element: &Element) -> Result<BreakpointSetResponse, anyhow::Error> {
+    Ok(BreakpointSetResponse {
+        id: match element.attributes.get("id") {
+            Some(id) => id.to_string(),
+            None => anyhow::bail!("Expected id to be set"),
+        },
+        state: BreakpointState::from_str(
+            element
+                .attributes
+                .get("state")
+                .map_or("enabled", |s| s.as_str()),
+        ),
+    })
+}
+
+fn parse_breakpoints(element: &Element) -> Vec<Breakpoint> {
+    let mut breakpoints: Vec<Breakpoint> = Vec::new();
+    for ce in &element.children {
+        let breakpoint_el = match ce {
+            XMLNode::Element(element) => element,
+            _ => continue,
+        };
+        if breakpoint_el.name != "breakpoint" {
+            continue;
+        }
+        breakpoints.push(Breakpoint {
+            id: breakpoint_el.attributes.get("id").map(|s| s.to_string()),
+            breakpoint_type: BreakpointType::from_str(
+                breakpoint_el
+                    .attributes
+                    .get("type")
+                    .map_or("line", |s| s.as_str()),
+            ),
+            state: BreakpointState::from_str(
+                breakpoint_el
+                    .attributes
+                    .get("state")
+                    .map_or("enabled", |s| s.as_str()),
+            ),
+            filename: breakpoint_el.attributes.get("filename").map(|s| s.to_string()),
+            line_no: breakpoint_el
+                .attributes
+                .get("lineno")
+                .and_then(|s| s.parse::<u32>().ok()),
+            function: match (
+                breakpoint_el.attributes.get("class"),
+                breakpoint_el.attributes.get("function"),
+            ) {
+                (Some(class), Some(function)) => Some(format!("{}::{}", class, function)),
+                (None, Some(function)) => Some(function.to_string()),
+                _ => None,
+            },
+            exception: breakpoint_el.attributes.get("exception").map(|s| s.to_string()),
+            expression: decode_element(breakpoint_el.get_child("expression")),
+            hit_value: breakpoint_el
+                .attributes
+                .get("hit_value")
+                .and_then(|s| s.parse::<u32>().ok())
+                .filter(|v| *v > 0),
+            hit_condition: breakpoint_el
+                .attributes
+                .get("hit_condition")
+                .and_then(|s| HitCondition::from_operator(s)),
+            hit_count: breakpoint_el
+                .attributes
+                .get("hit_count")
+                .and_then(|s| s.parse::<u32>().ok()),
+            temporary: breakpoint_el.attributes.get("temporary").is_some_and(|t| t == "1"),
+        });
+    }
+
+    breakpoints
+}
+
 fn parse_stack_get(element: &Element) -> StackGetResponse {
     let mut entries: Vec<StackEntry> = Vec::new();
     for ce in &element.children {
@@ -583,6 +1436,7 @@ fn parse_stack_get(element: &Element) -> StackGetResponse {
                 .expect("Expected level to be set")
                 .parse()
                 .unwrap(),
+            function: stack_el.attributes.get("where").map(|w| w.to_string()),
         };
         entries.push(entry);
     }
@@ -590,25 +1444,30 @@ fn parse_stack_get(element: &Element) -> StackGetResponse {
     StackGetResponse { entries }
 }
 
-fn parse_continuation_response(
-    attributes: &std::collections::HashMap<String, String>,
-) -> ContinuationResponse {
+fn parse_continuation_response(element: &Element) -> ContinuationResponse {
+    let attributes = &element.attributes;
     let status = attributes.get("status").expect("Expected status to be set");
     ContinuationResponse {
         status: match status.as_str() {
             "break" => ContinuationStatus::Break,
             "stopping" => ContinuationStatus::Stopping,
+            "stopped" => ContinuationStatus::Stopped,
             _ => ContinuationStatus::Unknown(status.to_string()),
         },
-        reason: attributes
-            .get("reason")
-            .expect("Expected reason to be set")
-            .to_string(),
+        reason: attributes.get("reason").cloned().unwrap_or_default(),
+        // xdebug provides details of the exception in a `xdebug:message` element
+        exception: element.get_child("message").and_then(|message| {
+            Some(ContinuationException {
+                class: message.attributes.get("exception")?.to_string(),
+                message: decode_element(Some(message)).unwrap_or_default(),
+                code: message.attributes.get("code").map(|s| s.to_string()),
+            })
+        }),
     }
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
     use pretty_assertions::assert_eq;
 
@@ -622,6 +1481,7 @@ mod test {
         match result {
             Message::Init(init) => {
                 assert_eq!("file:///application/vendor/bin/codecept", init.fileuri);
+                assert_eq!(None, init.idekey);
             }
             _ => panic!("Did not parse"),
         }
@@ -660,7 +1520,8 @@ mod test {
             Message::Response(r) => {
                 match r.command {
                     CommandResponse::StackGet(s) => {
-                        assert_eq!(3, s.entries.len())
+                        assert_eq!(3, s.entries.len());
+                        assert_eq!(Some("call_function"), s.entries[1].function.as_deref());
                     }
                     _ => panic!("Could not parse get_stack"),
                 };
@@ -719,6 +1580,7 @@ function call_function(string $hello) {
                                 classname: None,
                                 page: None,
                                 pagesize: None,
+                                numchildren: None,
                                 property_type: PropertyType::Int,
                                 facet: None,
                                 size: None,
@@ -739,6 +1601,42 @@ function call_function(string $hello) {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_property_get_page() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"
+            <response xmlns="urn:debugger_protocol_v1" command="property_get" transaction_id="7"><property name="$big" fullname="$big" type="array" children="1" numchildren="10000" page="1" pagesize="2"><property name="2" fullname="$big[2]" type="int"><![CDATA[2]]></property><property name="3" fullname="$big[3]" type="int"><![CDATA[3]]></property></property></response>
+            "#,
+        )?;
+
+        match result {
+            Message::Response(r) => match r.command {
+                CommandResponse::PropertyGet(property) => {
+                    assert_eq!(Some(10000), property.numchildren);
+                    assert_eq!(Some(1), property.page);
+                    assert_eq!(
+                        vec!["$big[2]", "$big[3]"],
+                        property
+                            .children
+                            .properties
+                            .iter()
+                            .map(|p| p.fullname.as_str())
+                            .collect::<Vec<&str>>()
+                    );
+                    assert_eq!(9998, property.remaining_children());
+                }
+                _ => panic!("Could not parse property_get"),
+            },
+            _ => panic!("Did not parse"),
+        };
+
+        let result = parse_xml(
+            r#"<response xmlns="urn:debugger_protocol_v1" command="property_get" transaction_id="8"><error code="300"><message><![CDATA[can not get property]]></message></error></response>"#,
+        );
+        assert!(result.is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_parse_eval_error() -> Result<(), anyhow::Error> {
         let result = parse_xml(
@@ -770,6 +1668,39 @@ function call_function(string $hello) {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_context_names() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"
+            <response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="context_names" transaction_id="3"><context name="Locals" id="0"></context><context name="Superglobals" id="1"></context><context name="User defined constants" id="2"></context></response>
+            "#,
+        )?;
+
+        match result {
+            Message::Response(r) => match r.command {
+                CommandResponse::ContextNames(names) => {
+                    assert_eq!(
+                        vec![
+                            ContextName::locals(),
+                            ContextName {
+                                id: 1,
+                                name: "Superglobals".to_string()
+                            },
+                            ContextName {
+                                id: 2,
+                                name: "User defined constants".to_string()
+                            },
+                        ],
+                        names
+                    );
+                }
+                _ => panic!("Could not parse context_names"),
+            },
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
     #[test]
     fn test_parse_context_get() -> Result<(), anyhow::Error> {
         let result = parse_xml(
@@ -800,6 +1731,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::String,
                                     facet: None,
                                     size: Some(3),
@@ -815,6 +1747,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::Float,
                                     facet: None,
                                     size: None,
@@ -830,6 +1763,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::Int,
                                     facet: None,
                                     size: None,
@@ -845,6 +1779,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::Bool,
                                     facet: None,
                                     size: None,
@@ -860,6 +1795,7 @@ function call_function(string $hello) {
                                     classname: Some("Foo".to_string()),
                                     page: Some(0),
                                     pagesize: Some(32),
+                                    numchildren: Some(2),
                                     property_type: PropertyType::Object,
                                     facet: None,
                                     size: None,
@@ -870,6 +1806,7 @@ function call_function(string $hello) {
                                             classname: None,
                                             page: None,
                                             pagesize: None,
+                                            numchildren: None,
                                             property_type: PropertyType::Bool,
                                             facet: Some("public".to_string()),
                                             size: None,
@@ -885,6 +1822,7 @@ function call_function(string $hello) {
                                             classname: None,
                                             page: None,
                                             pagesize: None,
+                                            numchildren: None,
                                             property_type: PropertyType::String,
                                             facet: Some("public".to_string()),
                                             size: Some(3),
@@ -900,6 +1838,7 @@ function call_function(string $hello) {
                                             classname: None,
                                             page: None,
                                             pagesize: None,
+                                            numchildren: None,
                                             property_type: PropertyType::Resource,
                                             facet: Some("private".to_string()),
                                             size: None,
@@ -923,6 +1862,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::Array,
                                     facet: None,
                                     size: None,
@@ -973,6 +1913,7 @@ function call_function(string $hello) {
                                 classname: None,
                                 page: Some(0),
                                 pagesize: Some(32),
+                                numchildren: Some(5),
                                 property_type: PropertyType::Array,
                                 facet: None,
                                 size: None,
@@ -982,6 +1923,7 @@ function call_function(string $hello) {
                                     classname: None,
                                     page: None,
                                     pagesize: None,
+                                    numchildren: None,
                                     property_type: PropertyType::Int,
                                     facet: None,
                                     size: None,
@@ -1006,4 +1948,424 @@ function call_function(string $hello) {
         };
         Ok(())
     }
+
+    #[test]
+    fn test_parse_breakpoint_set() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="breakpoint_set" transaction_id="3" id="1660001" state="enabled"></response>"#,
+        )?;
+
+        match result {
+            Message::Response(r) => {
+                match r.command {
+                    CommandResponse::BreakpointSet(response) => {
+                        assert_eq!(
+                            BreakpointSetResponse {
+                                id: "1660001".to_string(),
+                                state: BreakpointState::Enabled,
+                            },
+                            response
+                        )
+                    }
+                    _ => panic!("Could not parse breakpoint_set"),
+                };
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_breakpoint_list() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="breakpoint_list" transaction_id="5"><breakpoint type="line" filename="file:///app/test.php" lineno="10" state="enabled" hit_count="0" hit_value="0" id="1660001"></breakpoint><breakpoint type="conditional" filename="file:///app/test.php" lineno="12" state="disabled" hit_count="3" hit_value="4000" hit_condition="&gt;=" id="1660002"><expression encoding="base64"><![CDATA[JGkgPiAz]]></expression></breakpoint></response>"#,
+        )?;
+
+        match result {
+            Message::Response(r) => {
+                match r.command {
+                    CommandResponse::BreakpointList(breakpoints) => {
+                        assert_eq!(
+                            vec![
+                                Breakpoint {
+                                    id: Some("1660001".to_string()),
+                                    breakpoint_type: BreakpointType::Line,
+                                    state: BreakpointState::Enabled,
+                                    filename: Some("file:///app/test.php".to_string()),
+                                    line_no: Some(10),
+                                    hit_count: Some(0),
+                                    ..Default::default()
+                                },
+                                Breakpoint {
+                                    id: Some("1660002".to_string()),
+                                    breakpoint_type: BreakpointType::Conditional,
+                                    state: BreakpointState::Disabled,
+                                    filename: Some("file:///app/test.php".to_string()),
+                                    line_no: Some(12),
+                                    function: None,
+                                    exception: None,
+                                    expression: Some("$i > 3".to_string()),
+                                    hit_value: Some(4000),
+                                    hit_condition: Some(HitCondition::GreaterOrEqual),
+                                    hit_count: Some(3),
+                                    temporary: false,
+                                },
+                            ],
+                            breakpoints
+                        )
+                    }
+                    _ => panic!("Could not parse breakpoint_list"),
+                };
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_breakpoint_get() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="breakpoint_get" transaction_id="7"><breakpoint type="line" filename="file:///app/test.php" lineno="10" state="enabled" hit_count="12" hit_value="10" hit_condition="%" id="1660001"></breakpoint></response>"#,
+        )?;
+
+        match result {
+            Message::Response(r) => {
+                match r.command {
+                    CommandResponse::BreakpointGet(breakpoint) => {
+                        assert_eq!(Some(12), breakpoint.hit_count);
+                        assert_eq!(Some(10), breakpoint.hit_value);
+                        assert_eq!(Some(HitCondition::Multiple), breakpoint.hit_condition);
+                    }
+                    _ => panic!("Could not parse breakpoint_get"),
+                };
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_breakpoint_list_exception_and_call() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="breakpoint_list" transaction_id="5"><breakpoint type="exception" exception="Doctrine\DBAL\Exception" state="enabled" hit_count="0" hit_value="0" id="1660003"></breakpoint><breakpoint type="call" function="bar" class="Foo" state="enabled" hit_count="1" hit_value="0" id="1660004"></breakpoint></response>"#,
+        )?;
+
+        match result {
+            Message::Response(r) => {
+                match r.command {
+                    CommandResponse::BreakpointList(breakpoints) => {
+                        assert_eq!(BreakpointType::Exception, breakpoints[0].breakpoint_type);
+                        assert_eq!(
+                            Some("Doctrine\\DBAL\\Exception".to_string()),
+                            breakpoints[0].exception
+                        );
+                        assert_eq!(BreakpointType::Call, breakpoints[1].breakpoint_type);
+                        assert_eq!(Some("Foo::bar".to_string()), breakpoints[1].function);
+                    }
+                    _ => panic!("Could not parse breakpoint_list"),
+                };
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_continuation_exception() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<response xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" command="run" transaction_id="9" status="break" reason="ok"><xdebug:message filename="file:///app/test.php" lineno="5" exception="RuntimeException" code="12"><![CDATA[Something went wrong]]></xdebug:message></response>"#,
+        )?;
+
+        match result {
+            Message::Response(r) => {
+                match r.command {
+                    CommandResponse::Run(response) => {
+                        assert_eq!(
+                            Some(ContinuationException {
+                                class: "RuntimeException".to_string(),
+                                message: "Something went wrong".to_string(),
+                                code: Some("12".to_string()),
+                            }),
+                            response.exception
+                        );
+                    }
+                    _ => panic!("Could not parse run"),
+                };
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_stream() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<stream xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" type="stdout" encoding="base64"><![CDATA[SGVsbG8gV29ybGQK]]></stream>"#,
+        )?;
+
+        match result {
+            Message::Stream(stream) => {
+                assert_eq!(
+                    Stream {
+                        stream_type: StreamType::Stdout,
+                        data: "Hello World\n".to_string(),
+                    },
+                    stream
+                );
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_notify() -> Result<(), anyhow::Error> {
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<notify xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" name="error"><xdebug:message filename="file:///app/test.php" lineno="7" type="Notice" code="8"><![CDATA[Undefined variable: foo]]></xdebug:message></notify>"#,
+        )?;
+        match result {
+            Message::Notify(notify) => assert_eq!(
+                EngineNotify::Error(NotifyError {
+                    error_type: "Notice".to_string(),
+                    message: "Undefined variable: foo".to_string(),
+                    filename: Some("file:///app/test.php".to_string()),
+                    line_no: Some(7),
+                }),
+                notify
+            ),
+            _ => panic!("Did not parse"),
+        };
+
+        let result = parse_xml(
+            r#"<?xml version="1.0" encoding="iso-8859-1"?>
+<notify xmlns="urn:debugger_protocol_v1" xmlns:xdebug="https://xdebug.org/dbgp/xdebug" name="breakpoint_resolved"><breakpoint type="line" resolved="resolved" filename="file:///app/test.php" lineno="12" state="enabled" hit_count="0" hit_value="0" id="1660002"></breakpoint></notify>"#,
+        )?;
+        match result {
+            Message::Notify(EngineNotify::BreakpointResolved(breakpoint)) => {
+                assert_eq!(Some("1660002".to_string()), breakpoint.id);
+                assert_eq!(Some(12), breakpoint.line_no);
+            }
+            _ => panic!("Did not parse"),
+        };
+        Ok(())
+    }
+
+    /// connect a client to an engine which answers each command with the
+    /// next response
+    pub(crate) async fn conversing_engine(
+        responses: &'static [&'static str],
+    ) -> Result<(DbgpClient, tokio::task::JoinHandle<Vec<String>>), anyhow::Error> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let engine = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = socket.into_split();
+            let mut reader = BufReader::new(reader);
+            let mut received = vec![];
+            for xml in responses {
+                let mut command = vec![];
+                reader.read_until(b'\0', &mut command).await.unwrap();
+                command.pop();
+                received.push(String::from_utf8(command).unwrap());
+                writer
+                    .write_all(format!("{}\0{}\0", xml.len(), xml).as_bytes())
+                    .await
+                    .unwrap();
+            }
+            received
+        });
+        Ok((DbgpClient::new(Some(TcpStream::connect(address).await?)), engine))
+    }
+
+    /// connect a client to an engine which waits for the given number of
+    /// commands and then sends the packets at once
+    async fn stand_in_engine(
+        commands: usize,
+        xml: &'static [&'static str],
+    ) -> Result<(DbgpClient, tokio::task::JoinHandle<Vec<String>>), anyhow::Error> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let engine = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = vec![];
+            {
+                let mut reader = BufReader::new(&mut socket);
+                for _ in 0..commands {
+                    let mut command = vec![];
+                    reader.read_until(b'\0', &mut command).await.unwrap();
+                    command.pop();
+                    received.push(String::from_utf8(command).unwrap());
+                }
+            }
+            let mut packets = vec![];
+            for xml in xml {
+                packets.extend(format!("{}\0{}\0", xml.len(), xml).into_bytes());
+            }
+            socket.write_all(&packets).await.unwrap();
+            received
+        });
+        Ok((DbgpClient::new(Some(TcpStream::connect(address).await?)), engine))
+    }
+
+    #[tokio::test]
+    async fn test_command_buffers_stream_packets() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(1, &[
+            r#"<stream type="stderr" encoding="base64"><![CDATA[V2FybmluZw==]]></stream>"#,
+            r#"<response command="stdout" transaction_id="0" success="1"></response>"#,
+        ])
+        .await?;
+        client.stdout(OutputMode::Copy).await?;
+        engine.await?;
+
+        assert_eq!(
+            vec![Stream {
+                stream_type: StreamType::Stderr,
+                data: "Warning".to_string(),
+            }],
+            client.take_output()
+        );
+        assert!(client.take_output().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_command_demultiplexes_notify_packets() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(1, &[
+            r#"<notify name="breakpoint_resolved"></notify>"#,
+            r#"<response command="stdout" transaction_id="41" success="1"></response>"#,
+            r#"<response command="stdout" transaction_id="0" success="0"></response>"#,
+        ])
+        .await?;
+        let error = client.stdout(OutputMode::Copy).await.unwrap_err();
+        engine.await?;
+
+        assert_eq!("Engine does not support stdout", error.to_string());
+        assert_eq!(
+            vec![EngineNotify::Other("breakpoint_resolved".to_string())],
+            client.take_notifications()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_routed_by_transaction_id() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(2, &[
+            r#"<response command="break" transaction_id="1" success="1"></response>"#,
+            r#"<response command="run" transaction_id="0" status="break" reason="ok"></response>"#,
+        ])
+        .await?;
+        // break can be sent while run is outstanding
+        let run = client.run().await?;
+        client.break_execution().await?.broken().await?;
+        assert_eq!(vec!["run -i 0", "break -i 1"], engine.await?);
+        assert_eq!(ContinuationStatus::Break, run.continuation().await?.status);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pending_command_fails_when_connection_closes() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(1, &[]).await?;
+        let pending = client.send("stack_get", &mut []).await?;
+        engine.await?;
+
+        assert_eq!("Connection was closed", pending.response().await.unwrap_err().to_string());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_after_engine_hung_up() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(0, &[]).await?;
+        engine.await?;
+        // wait for the reader to see the end of the stream
+        client.reader.take().unwrap().await?;
+
+        assert!(!client.is_connected());
+        assert_eq!(
+            Some("Stream was closed".to_string()),
+            client.send("stack_get", &mut []).await.err().map(|e| e.to_string())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_detach() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(2, &[
+            r#"<response command="detach" transaction_id="0" status="stopping" reason="ok"></response>"#,
+            r#"<response command="stop" transaction_id="1" status="stopped"></response>"#,
+        ])
+        .await?;
+        let detach = client.send("detach", &mut []).await?;
+        let stop = client.stop();
+        let (detach, stop) = tokio::join!(detach.response(), stop);
+        assert_eq!(vec!["detach -i 0", "stop -i 1"], engine.await?);
+
+        match detach? {
+            Message::Response(Response {
+                command: CommandResponse::Detach(response),
+                ..
+            }) => assert_eq!(ContinuationStatus::Stopping, response.status),
+            _ => panic!("Expected detach response"),
+        };
+        assert_eq!(ContinuationStatus::Stopped, stop?.status);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_property_set() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(1, &[
+            r#"<response command="property_set" transaction_id="0" success="1"></response>"#,
+        ])
+        .await?;
+        client
+            .property_set("$foo[\"bar\"]", 1, 0, PropertyType::Int, "12")
+            .await?;
+        assert_eq!(
+            vec![r#"property_set -i 0 -d 1 -c 0 -t int -n "$foo[\"bar\"]" -- MTI="#],
+            engine.await?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_temporary_breakpoint_set() -> Result<(), anyhow::Error> {
+        let (mut client, engine) = stand_in_engine(1, &[
+            r#"<response command="breakpoint_set" transaction_id="0" id="42" state="enabled"></response>"#,
+        ])
+        .await?;
+        let mut breakpoint = Breakpoint::line("file:///app/foo.php".to_string(), 12);
+        breakpoint.temporary = true;
+        assert_eq!("42", client.breakpoint_set(&breakpoint).await?.id);
+        assert_eq!(
+            vec!["breakpoint_set -i 0 -t line -s enabled -f file:///app/foo.php -n 12 -r 1"],
+            engine.await?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_truthy() {
+        let property = |property_type: PropertyType, value: &str| Property {
+            property_type,
+            value: Some(value.to_string()),
+            ..Default::default()
+        };
+        assert!(property(PropertyType::Bool, "1").is_truthy());
+        assert!(!property(PropertyType::Bool, "0").is_truthy());
+        assert!(property(PropertyType::Int, "-3").is_truthy());
+        assert!(!property(PropertyType::Int, "0").is_truthy());
+        assert!(!property(PropertyType::Float, "0.0").is_truthy());
+        assert!(property(PropertyType::String, "0.0").is_truthy());
+        assert!(!property(PropertyType::String, "0").is_truthy());
+        assert!(!property(PropertyType::String, "").is_truthy());
+        assert!(!property(PropertyType::Array, "").is_truthy());
+        assert!(property(PropertyType::Object, "").is_truthy());
+        assert!(!property(PropertyType::Null, "").is_truthy());
+    }
 }
@@ -0,0 +1,147 @@
+use anyhow::Result;
+use log::debug;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use xmltree::Element;
+
+const PROXY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyInitResponse {
+    pub idekey: String,
+    pub address: Option<String>,
+    pub port: Option<u16>,
+}
+
+/// register with the DBGp proxy, the proxy will then forward connections for
+/// the IDE key to the given port
+pub async fn proxy_init(proxy: &str, port: u16, idekey: &str) -> Result<ProxyInitResponse> {
+    let root = proxy_command(proxy, &format!("proxyinit -p {} -k {} -m 1", port, idekey)).await?;
+    Ok(ProxyInitResponse {
+        idekey: root
+            .attributes
+            .get("idekey")
+            .cloned()
+            .unwrap_or(idekey.to_string()),
+        address: root.attributes.get("address").cloned(),
+        port: root.attributes.get("port").and_then(|p| p.parse().ok()),
+    })
+}
+
+/// unregister the IDE key from the DBGp proxy
+pub async fn proxy_stop(proxy: &str, idekey: &str) -> Result<()> {
+    proxy_command(proxy, &format!("proxystop -k {}", idekey)).await?;
+    Ok(())
+}
+
+// the proxy handles one command per connection and closes the connection
+// after responding
+async fn proxy_command(proxy: &str, command: &str) -> Result<Element> {
+    let response = timeout(PROXY_TIMEOUT, async {
+        let mut stream = TcpStream::connect(proxy).await?;
+        debug!("[proxy] >> {}", command);
+        stream.write_all([command, "\0"].concat().as_bytes()).await?;
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await?;
+        Ok::<Vec<u8>, anyhow::Error>(response)
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Timed out waiting for the DBGp proxy at {}", proxy))??;
+
+    let response = String::from_utf8_lossy(&response);
+    debug!("[proxy] << {}", response);
+    parse_proxy_response(&response)
+}
+
+fn parse_proxy_response(response: &str) -> Result<Element> {
+    // some proxies frame the response like the engine does: `length\0xml\0`
+    let xml = response.trim_end_matches('\0');
+    let xml = match xml.split_once('\0') {
+        Some((_, xml)) => xml,
+        None => xml,
+    };
+    let root = Element::parse(xml.trim().as_bytes())?;
+    if root.attributes.get("success").map(|s| s.as_str()) != Some("1") {
+        let message = root
+            .get_child("error")
+            .and_then(|e| e.get_child("message"))
+            .and_then(|m| m.get_text())
+            .map(|m| m.to_string())
+            .unwrap_or("unknown error".to_string());
+        anyhow::bail!("{} failed: {}", root.name, message);
+    }
+    Ok(root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::net::TcpListener;
+
+    // accept a single command and reply with the given response
+    async fn stand_in_proxy(response: &'static str) -> Result<(String, tokio::task::JoinHandle<String>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?.to_string();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut command = vec![];
+            loop {
+                let byte = stream.read_u8().await.unwrap();
+                if byte == 0 {
+                    break;
+                }
+                command.push(byte);
+            }
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(command).unwrap()
+        });
+        Ok((address, handle))
+    }
+
+    #[tokio::test]
+    async fn test_proxy_init() -> Result<()> {
+        let (address, proxy) = stand_in_proxy(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<proxyinit success="1" idekey="dan" address="127.0.0.1" port="9003"/>"#,
+        )
+        .await?;
+
+        let response = proxy_init(&address, 9003, "dan").await?;
+        assert_eq!("proxyinit -p 9003 -k dan -m 1", proxy.await?);
+        assert_eq!(
+            ProxyInitResponse {
+                idekey: "dan".to_string(),
+                address: Some("127.0.0.1".to_string()),
+                port: Some(9003),
+            },
+            response
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_proxy_stop() -> Result<()> {
+        let (address, proxy) =
+            stand_in_proxy("98\0<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<proxystop success=\"1\" idekey=\"dan\"/>\0").await?;
+
+        proxy_stop(&address, "dan").await?;
+        assert_eq!("proxystop -k dan", proxy.await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_proxy_error() -> Result<()> {
+        let (address, _) = stand_in_proxy(
+            r#"<proxyinit success="0"><error id="1"><message>IDE Key already exists</message></error></proxyinit>"#,
+        )
+        .await?;
+
+        let error = proxy_init(&address, 9003, "dan").await.unwrap_err();
+        assert_eq!("proxyinit failed: IDE Key already exists", error.to_string());
+        Ok(())
+    }
+}
@@ -12,7 +12,10 @@ use tokio::net::TcpStream;
 use tokio::sync::mpsc::Sender;
 
 use crate::app::SelectedView;
-use crate::dbgp::client::ContinuationStatus;
+use crate::app::SessionId;
+use crate::app::StepUntilOutcome;
+use crate::dbgp::client::BreakpointType;
+use crate::dbgp::client::ContinuationResponse;
 use crate::view::session::SessionViewMode;
 use crate::view::Scroll;
 
@@ -21,22 +24,27 @@ pub enum AppEvent {
     ChangeSessionViewMode(SessionViewMode),
     ChangeView(SelectedView),
     ClientConnected(TcpStream),
-    Disconnect,
+    Disconnect(SessionId),
     HistoryNext,
     HistoryPrevious,
     Input(KeyEvent),
     Panic(String),
     Quit,
     Run,
+    Interrupt,
+    /// the response to interrupting the script
+    Interrupted(Result<(), String>),
+    Stop,
+    Detach,
     SessionStarted,
-    Snapshot(),
+    Snapshot(SessionId),
     Startup,
     StepInto,
     StepOut,
     StepOver,
     Tick,
     UpdateSourceContext(String, String, u32),
-    UpdateStatus(ContinuationStatus),
+    UpdateStatus(SessionId, ContinuationResponse),
     NextPane,
     PreviousPane,
     Scroll(Scroll),
@@ -44,17 +52,51 @@ pub enum AppEvent {
     ScrollContext(Scroll),
     ScrollStack(Scroll),
     ScrollEval(Scroll),
+    ScrollBreakpoints(Scroll),
+    ScrollOutput(Scroll),
+    ScrollSessions(Scroll),
+    SwitchSession,
+    CloseSession,
     ToggleFullscreen,
+    ToggleBreakpoint,
     PushInputPlurality(char),
     ContextDepth(i8),
     NextTheme,
     ContextFilterOpen,
     ContextSearchClose,
+    ContextSwitch,
+    PropertyEdit,
+    PropertyDialogCancel,
+    PropertyDialogApply,
+    ScrollWatches(Scroll),
+    ScrollTimeline(Scroll),
+    /// show the value of the property with the fullname at every step
+    Timeline(String),
+    TimelineJump,
+    RunToCursor,
+    StepUntilStart,
+    StepUntilDialogCancel,
+    StepUntilDialogApply,
+    StepUntilProgress(SessionId, usize),
+    StepUntilDone(SessionId, StepUntilOutcome),
+    WatchAdd,
+    WatchEdit,
+    WatchDelete,
+    WatchDialogCancel,
+    WatchDialogApply,
+    ContextFetchChildren(String),
     Listen,
     EvalCancel,
     EvalExecute,
     EvalRefresh,
     EvalStart,
+    BreakpointEdit,
+    BreakpointAdd(BreakpointType),
+    BreakpointDialogCancel,
+    BreakpointDialogApply,
+    BreakpointToggleState,
+    BreakpointDelete,
+    BreakpointJump,
 }
 
 pub type EventSender = Sender<AppEvent>;
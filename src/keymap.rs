@@ -0,0 +1,564 @@
+use anyhow::Result;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// where an action is available. Scopes are consulted from the most general
+/// to the most specific, e.g. a [Scope::Session] binding takes precedence
+/// over a [Scope::Source] binding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    Global,
+    Session,
+    Current,
+    History,
+    Source,
+    Context,
+    Breakpoints,
+    Watches,
+    Sessions,
+    Dialog,
+}
+
+impl Scope {
+    pub fn title(&self) -> &str {
+        match self {
+            Scope::Global => "Global",
+            Scope::Session => "Session",
+            Scope::Current => "Current mode",
+            Scope::History => "History mode",
+            Scope::Source => "Source pane",
+            Scope::Context => "Context pane",
+            Scope::Breakpoints => "Breakpoints pane",
+            Scope::Watches => "Watches pane",
+            Scope::Sessions => "Session list",
+            Scope::Dialog => "Dialogs and inputs",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    ThemeNext,
+    Help,
+    Sessions,
+    PaneNext,
+    PanePrevious,
+    PaneFullscreen,
+    ScrollDown,
+    ScrollDown10,
+    ScrollUp,
+    ScrollUp10,
+    ScrollLeft,
+    ScrollLeft10,
+    ScrollRight,
+    ScrollRight10,
+    Eval,
+    BreakpointAddException,
+    BreakpointAddCall,
+    BreakpointAddReturn,
+    ContextDepthIncrease,
+    ContextDepthDecrease,
+    Run,
+    StepInto,
+    StepOver,
+    StepOut,
+    StepUntil,
+    Disconnect,
+    Stop,
+    Detach,
+    Interrupt,
+    HistoryEnter,
+    HistoryNext,
+    HistoryPrevious,
+    HistoryExit,
+    BreakpointToggle,
+    BreakpointEdit,
+    RunToCursor,
+    ContextFilter,
+    ContextToggle,
+    ContextMore,
+    ContextParent,
+    ContextNextSibling,
+    ContextPreviousSibling,
+    ContextCopyName,
+    ContextSwitch,
+    ContextEdit,
+    ContextTimeline,
+    BreakpointToggleState,
+    BreakpointDelete,
+    BreakpointJump,
+    WatchAdd,
+    WatchEdit,
+    WatchDelete,
+    SessionSwitch,
+    SessionClose,
+    DialogApply,
+    DialogCancel,
+    DialogNextField,
+    DialogPreviousField,
+}
+
+/// the default bindings, in the order in which they are shown in the help
+const DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::ThemeNext, &["t"]),
+    (Action::Help, &["?"]),
+    (Action::Sessions, &["S"]),
+    (Action::PaneNext, &["tab"]),
+    (Action::PanePrevious, &["backtab"]),
+    (Action::PaneFullscreen, &["enter"]),
+    (Action::ScrollDown, &["j", "down"]),
+    (Action::ScrollDown10, &["J", "shift-down"]),
+    (Action::ScrollUp, &["k", "up"]),
+    (Action::ScrollUp10, &["K", "shift-up"]),
+    (Action::ScrollLeft, &["h", "left"]),
+    (Action::ScrollLeft10, &["H", "shift-left"]),
+    (Action::ScrollRight, &["l", "right"]),
+    (Action::ScrollRight10, &["L", "shift-right"]),
+    (Action::Eval, &["e"]),
+    (Action::BreakpointAddException, &["X"]),
+    (Action::BreakpointAddCall, &["C"]),
+    (Action::BreakpointAddReturn, &["R"]),
+    (Action::Disconnect, &["d"]),
+    (Action::Stop, &["Q"]),
+    (Action::Detach, &["D"]),
+    (Action::Interrupt, &["i"]),
+    (Action::Run, &["r"]),
+    (Action::StepInto, &["n"]),
+    (Action::StepOver, &["N"]),
+    (Action::StepOut, &["o"]),
+    (Action::StepUntil, &["U"]),
+    (Action::HistoryEnter, &["p"]),
+    (Action::ContextDepthIncrease, &["+"]),
+    (Action::ContextDepthDecrease, &["-"]),
+    (Action::HistoryNext, &["n"]),
+    (Action::HistoryPrevious, &["p"]),
+    (Action::HistoryExit, &["esc"]),
+    (Action::BreakpointToggle, &["b"]),
+    (Action::BreakpointEdit, &["B"]),
+    (Action::RunToCursor, &["g"]),
+    (Action::ContextFilter, &["f"]),
+    (Action::ContextToggle, &["space"]),
+    (Action::ContextMore, &["m"]),
+    (Action::ContextParent, &["u"]),
+    (Action::ContextNextSibling, &["]"]),
+    (Action::ContextPreviousSibling, &["["]),
+    (Action::ContextCopyName, &["y"]),
+    (Action::ContextSwitch, &["c"]),
+    (Action::ContextEdit, &["="]),
+    (Action::ContextTimeline, &["T"]),
+    (Action::BreakpointToggleState, &["s"]),
+    (Action::BreakpointDelete, &["x"]),
+    (Action::BreakpointJump, &["g"]),
+    (Action::WatchAdd, &["a"]),
+    (Action::WatchEdit, &["c"]),
+    (Action::WatchDelete, &["x"]),
+    (Action::SessionSwitch, &["enter"]),
+    (Action::SessionClose, &["x"]),
+    (Action::DialogApply, &["enter"]),
+    (Action::DialogCancel, &["esc"]),
+    (Action::DialogNextField, &["tab"]),
+    (Action::DialogPreviousField, &["backtab"]),
+];
+
+impl Action {
+    pub fn name(&self) -> &str {
+        match self {
+            Action::ThemeNext => "theme.next",
+            Action::Help => "help",
+            Action::Sessions => "sessions",
+            Action::PaneNext => "pane.next",
+            Action::PanePrevious => "pane.previous",
+            Action::PaneFullscreen => "pane.fullscreen",
+            Action::ScrollDown => "scroll.down",
+            Action::ScrollDown10 => "scroll.down_10",
+            Action::ScrollUp => "scroll.up",
+            Action::ScrollUp10 => "scroll.up_10",
+            Action::ScrollLeft => "scroll.left",
+            Action::ScrollLeft10 => "scroll.left_10",
+            Action::ScrollRight => "scroll.right",
+            Action::ScrollRight10 => "scroll.right_10",
+            Action::Eval => "eval",
+            Action::BreakpointAddException => "breakpoint.add_exception",
+            Action::BreakpointAddCall => "breakpoint.add_call",
+            Action::BreakpointAddReturn => "breakpoint.add_return",
+            Action::ContextDepthIncrease => "context.depth_increase",
+            Action::ContextDepthDecrease => "context.depth_decrease",
+            Action::Run => "run",
+            Action::StepInto => "step_into",
+            Action::StepOver => "step_over",
+            Action::StepOut => "step_out",
+            Action::StepUntil => "step_until",
+            Action::Disconnect => "disconnect",
+            Action::Stop => "stop",
+            Action::Detach => "detach",
+            Action::Interrupt => "interrupt",
+            Action::HistoryEnter => "history.enter",
+            Action::HistoryNext => "history.next",
+            Action::HistoryPrevious => "history.previous",
+            Action::HistoryExit => "history.exit",
+            Action::BreakpointToggle => "breakpoint.toggle",
+            Action::BreakpointEdit => "breakpoint.edit",
+            Action::RunToCursor => "run_to_cursor",
+            Action::ContextFilter => "context.filter",
+            Action::ContextToggle => "context.toggle",
+            Action::ContextMore => "context.more",
+            Action::ContextParent => "context.parent",
+            Action::ContextNextSibling => "context.next_sibling",
+            Action::ContextPreviousSibling => "context.previous_sibling",
+            Action::ContextCopyName => "context.copy_name",
+            Action::ContextSwitch => "context.switch",
+            Action::ContextEdit => "context.edit",
+            Action::ContextTimeline => "context.timeline",
+            Action::BreakpointToggleState => "breakpoint.toggle_state",
+            Action::BreakpointDelete => "breakpoint.delete",
+            Action::BreakpointJump => "breakpoint.jump",
+            Action::WatchAdd => "watch.add",
+            Action::WatchEdit => "watch.edit",
+            Action::WatchDelete => "watch.delete",
+            Action::SessionSwitch => "session.switch",
+            Action::SessionClose => "session.close",
+            Action::DialogApply => "dialog.apply",
+            Action::DialogCancel => "dialog.cancel",
+            Action::DialogNextField => "dialog.next_field",
+            Action::DialogPreviousField => "dialog.previous_field",
+        }
+    }
+
+    pub fn scope(&self) -> Scope {
+        match self {
+            Action::ThemeNext | Action::Help | Action::Sessions => Scope::Global,
+            Action::Run
+            | Action::StepInto
+            | Action::StepOver
+            | Action::StepOut
+            | Action::StepUntil
+            | Action::HistoryEnter
+            | Action::ContextDepthIncrease
+            | Action::ContextDepthDecrease => Scope::Current,
+            Action::HistoryNext | Action::HistoryPrevious | Action::HistoryExit => Scope::History,
+            Action::BreakpointToggle | Action::BreakpointEdit | Action::RunToCursor => {
+                Scope::Source
+            }
+            Action::ContextFilter
+            | Action::ContextToggle
+            | Action::ContextMore
+            | Action::ContextParent
+            | Action::ContextNextSibling
+            | Action::ContextPreviousSibling
+            | Action::ContextCopyName
+            | Action::ContextSwitch
+            | Action::ContextEdit
+            | Action::ContextTimeline => Scope::Context,
+            Action::BreakpointToggleState | Action::BreakpointDelete | Action::BreakpointJump => {
+                Scope::Breakpoints
+            }
+            Action::WatchAdd | Action::WatchEdit | Action::WatchDelete => Scope::Watches,
+            Action::SessionSwitch | Action::SessionClose => Scope::Sessions,
+            Action::DialogApply
+            | Action::DialogCancel
+            | Action::DialogNextField
+            | Action::DialogPreviousField => Scope::Dialog,
+            _ => Scope::Session,
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            Action::ThemeNext => "rotate the theme",
+            Action::Help => "show this help",
+            Action::Sessions => "list the debugging sessions",
+            Action::PaneNext => "focus the next pane",
+            Action::PanePrevious => "focus the previous pane",
+            Action::PaneFullscreen => "toggle pane focus (full screen)",
+            Action::ScrollDown => "scroll down",
+            Action::ScrollDown10 => "scroll down 10",
+            Action::ScrollUp => "scroll up",
+            Action::ScrollUp10 => "scroll up 10",
+            Action::ScrollLeft => "scroll left",
+            Action::ScrollLeft10 => "scroll left 10",
+            Action::ScrollRight => "scroll right",
+            Action::ScrollRight10 => "scroll right 10",
+            Action::Eval => "enter an expression",
+            Action::BreakpointAddException => "add an exception breakpoint",
+            Action::BreakpointAddCall => "add a function call breakpoint",
+            Action::BreakpointAddReturn => "add a function return breakpoint",
+            Action::ContextDepthIncrease => "increase context depth",
+            Action::ContextDepthDecrease => "decrease context depth",
+            Action::Run => "run",
+            Action::StepInto => "next / step into",
+            Action::StepOver => "step over",
+            Action::StepOut => "step out",
+            Action::StepUntil => "step until an expression is true",
+            Action::Disconnect => "close the connection, the engine decides what happens to the script",
+            Action::Stop => "stop the script and end the session",
+            Action::Detach => "end the session and let the script run to completion",
+            Action::Interrupt => "interrupt the running script",
+            Action::HistoryEnter => "previous (switches to history mode)",
+            Action::HistoryNext => "next history entry",
+            Action::HistoryPrevious => "previous history entry",
+            Action::HistoryExit => "back to current mode",
+            Action::BreakpointToggle => "toggle breakpoint on the cursor line",
+            Action::BreakpointEdit => "edit breakpoint condition and hit count",
+            Action::RunToCursor => "run until the cursor line is reached",
+            Action::ContextFilter => "filter - use dot notation to filter on multiple levels",
+            Action::ContextToggle => "expand or collapse the selected property",
+            Action::ContextMore => "fetch the next page of children of the selected property",
+            Action::ContextParent => "select the parent of the selected property",
+            Action::ContextNextSibling => "select the next property on the same level",
+            Action::ContextPreviousSibling => "select the previous property on the same level",
+            Action::ContextCopyName => "copy the full name of the selected property to the eval input",
+            Action::ContextSwitch => "show the next context, e.g. superglobals or constants",
+            Action::ContextEdit => "set the value of the selected scalar property",
+            Action::ContextTimeline => "list the value of the selected property at every step",
+            Action::BreakpointToggleState => "enable/disable the selected breakpoint",
+            Action::BreakpointDelete => "delete the selected breakpoint",
+            Action::BreakpointJump => "show the selected breakpoint in the source pane",
+            Action::WatchAdd => "add a watch expression",
+            Action::WatchEdit => "edit the selected watch expression",
+            Action::WatchDelete => "delete the selected watch expression",
+            Action::SessionSwitch => "switch to the selected session",
+            Action::SessionClose => "disconnect and remove the selected session",
+            Action::DialogApply => "apply",
+            Action::DialogCancel => "cancel",
+            Action::DialogNextField => "next field",
+            Action::DialogPreviousField => "previous field",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        DEFAULT_BINDINGS
+            .iter()
+            .map(|(action, _)| *action)
+            .find(|action| action.name() == name)
+    }
+}
+
+/// a key with modifiers, e.g. `ctrl-n`, `shift-down` or `J`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn parse(chord: &str) -> Result<KeyChord> {
+        let (modifiers, key) = match chord.rsplit_once('-') {
+            Some((modifiers, key)) if !modifiers.is_empty() && !key.is_empty() => (modifiers, key),
+            _ => ("", chord),
+        };
+
+        let mut key_modifiers = KeyModifiers::NONE;
+        for modifier in modifiers.split('-').filter(|m| !m.is_empty()) {
+            key_modifiers |= match modifier {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => anyhow::bail!("Unknown modifier \"{}\" in key \"{}\"", modifier, chord),
+            };
+        }
+
+        let code = match key {
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            key => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => anyhow::bail!("Unknown key \"{}\"", chord),
+                }
+            }
+        };
+
+        Ok(Self::normalize(code, key_modifiers))
+    }
+
+    pub fn from_event(event: &KeyEvent) -> KeyChord {
+        Self::normalize(event.code, event.modifiers)
+    }
+
+    // the shift modifier is implied by upper case characters and back tab
+    fn normalize(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        KeyChord {
+            code,
+            modifiers: match code {
+                KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+                _ => modifiers,
+            },
+        }
+    }
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("backtab"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// maps key chords to named actions
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    pub bindings: Vec<(Action, Vec<KeyChord>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|(action, keys)| {
+                    (
+                        *action,
+                        keys.iter()
+                            .map(|key| KeyChord::parse(key).expect("default key binding is valid"))
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// replace the default keys of the named actions, e.g. `step_into = ["n", "f7"]`
+    pub fn with_overrides(overrides: &HashMap<String, Vec<String>>) -> Result<Keymap> {
+        let mut keymap = Keymap::default();
+        for (name, keys) in overrides {
+            let action = match Action::from_name(name) {
+                Some(action) => action,
+                None => anyhow::bail!("Unknown action \"{}\" in key bindings", name),
+            };
+            let chords = keys
+                .iter()
+                .map(|key| KeyChord::parse(key))
+                .collect::<Result<Vec<KeyChord>>>()?;
+            for (bound_action, bound_keys) in keymap.bindings.iter_mut() {
+                if *bound_action == action {
+                    *bound_keys = chords.clone();
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// return the action bound to the key event in the given scope
+    pub fn action(&self, scope: Scope, event: &KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(action, keys)| action.scope() == scope && keys.contains(&chord))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .filter(|(bound_action, _)| *bound_action == action)
+            .flat_map(|(_, keys)| keys.iter().map(|key| key.to_string()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_key_chord() -> Result<()> {
+        assert_eq!(
+            KeyChord { code: KeyCode::Char('n'), modifiers: KeyModifiers::CONTROL },
+            KeyChord::parse("ctrl-n")?
+        );
+        assert_eq!(
+            KeyChord { code: KeyCode::Down, modifiers: KeyModifiers::SHIFT },
+            KeyChord::parse("shift-down")?
+        );
+        assert_eq!(KeyChord::parse("J")?, KeyChord::parse("shift-J")?);
+        assert_eq!(
+            KeyChord { code: KeyCode::Char('-'), modifiers: KeyModifiers::NONE },
+            KeyChord::parse("-")?
+        );
+        assert!(KeyChord::parse("hyper-x").is_err());
+        assert!(KeyChord::parse("foo").is_err());
+
+        for key in ["ctrl-alt-x", "shift-left", "backtab", "+", "space"] {
+            assert_eq!(key, KeyChord::parse(key)?.to_string());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_action() {
+        let keymap = Keymap::default();
+        let event = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(Some(Action::StepInto), keymap.action(Scope::Current, &event));
+        assert_eq!(Some(Action::HistoryNext), keymap.action(Scope::History, &event));
+        assert_eq!(None, keymap.action(Scope::Session, &event));
+
+        let event = KeyEvent::new(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(Some(Action::ScrollDown10), keymap.action(Scope::Session, &event));
+    }
+
+    #[test]
+    fn test_overrides() -> Result<()> {
+        let keymap = Keymap::with_overrides(&HashMap::from([(
+            "step_into".to_string(),
+            vec!["s".to_string(), "ctrl-n".to_string()],
+        )]))?;
+        let event = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(Some(Action::StepInto), keymap.action(Scope::Current, &event));
+        let event = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert_eq!(None, keymap.action(Scope::Current, &event));
+        assert_eq!("s, ctrl-n", keymap.keys(Action::StepInto));
+
+        assert!(Keymap::with_overrides(&HashMap::from([(
+            "step_sideways".to_string(),
+            vec!["s".to_string()]
+        )]))
+        .is_err());
+        Ok(())
+    }
+}
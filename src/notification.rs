@@ -1,3 +1,4 @@
+use crate::dbgp::client::NotifyError;
 use std::time::Duration;
 use std::time::SystemTime;
 
@@ -60,6 +61,22 @@ impl Notification {
         }
     }
 
+    /// PHP errors are shown as errors, warnings and notices as warnings
+    pub(crate) fn engine_error(error: &NotifyError) -> Notification {
+        let mut message = format!("{}: {}", error.error_type, error.message);
+        if let (Some(filename), Some(line_no)) = (&error.filename, error.line_no) {
+            message.push_str(&format!(
+                " in {}:{}",
+                filename.rsplit('/').next().unwrap_or(filename),
+                line_no
+            ));
+        }
+        match error.error_type.to_lowercase().contains("error") {
+            true => Self::error(message),
+            false => Self::warning(message),
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn warning(message: String) -> Notification {
         Notification {
@@ -96,4 +113,20 @@ mod test {
         };
         assert_eq!(' ', notification.countdown_char());
     }
+
+    #[test]
+    fn test_engine_error() {
+        let mut error = NotifyError {
+            error_type: "Notice".to_string(),
+            message: "Undefined variable: foo".to_string(),
+            filename: Some("file:///app/test.php".to_string()),
+            line_no: Some(7),
+        };
+        let notification = Notification::engine_error(&error);
+        assert_eq!("Notice: Undefined variable: foo in test.php:7", notification.message);
+        assert!(matches!(notification.level, NotificationLevel::Warning));
+
+        error.error_type = "Fatal error".to_string();
+        assert!(matches!(Notification::engine_error(&error).level, NotificationLevel::Error));
+    }
 }
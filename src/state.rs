@@ -0,0 +1,81 @@
+use crate::dbgp::client::Breakpoint;
+use crate::theme::Theme;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// State which is persisted between invocations of debug-tui
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub theme: Option<Theme>,
+    pub context_depth: Option<u16>,
+    pub eval: Option<String>,
+    pub breakpoints: Vec<Breakpoint>,
+    pub watches: Vec<String>,
+}
+
+impl State {
+    /// load the state from the given path, returning the default state if
+    /// the file does not exist
+    pub fn load(path: &Path) -> Result<State> {
+        if !path.exists() {
+            return Ok(State::default());
+        }
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Could not parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dbgp::client::BreakpointType;
+    use crate::dbgp::client::HitCondition;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("debug-tui-state-{}", std::process::id()))
+            .join("state.toml");
+        assert_eq!(State::default(), State::load(&path)?);
+
+        let mut conditional = Breakpoint::line("file:///app/foo.php".to_string(), 12);
+        conditional.breakpoint_type = BreakpointType::Conditional;
+        conditional.expression = Some("$i > 100".to_string());
+        conditional.hit_condition = Some(HitCondition::GreaterOrEqual);
+        conditional.hit_value = Some(10);
+
+        let state = State {
+            theme: Some(Theme::SolarizedLight),
+            context_depth: Some(3),
+            eval: Some("$foo".to_string()),
+            watches: vec!["count($items)".to_string(), "$this->id".to_string()],
+            breakpoints: vec![
+                conditional,
+                Breakpoint {
+                    breakpoint_type: BreakpointType::Exception,
+                    exception: Some("Doctrine\\DBAL\\Exception".to_string()),
+                    ..Default::default()
+                },
+            ],
+        };
+        state.save(&path)?;
+        assert_eq!(state, State::load(&path)?);
+
+        fs::remove_dir_all(path.parent().unwrap())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::workspace::PathMappings;
+
+/// Glob patterns for files which are skipped when stepping, e.g.
+/// `*/vendor/*`. `*` matches any characters (including `/`) and `?` matches
+/// a single character.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StepFilters {
+    pub patterns: Vec<String>,
+}
+
+impl StepFilters {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self { patterns }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// the filename is a file URI or a path
+    pub fn is_filtered(&self, filename: &str) -> bool {
+        let path = filename.strip_prefix("file://").unwrap_or(filename);
+        self.patterns.iter().any(|pattern| glob_match(pattern, path))
+    }
+
+    /// match a file URI reported by the engine by its remote path and by
+    /// its path in the local checkout
+    pub fn is_filtered_remote(&self, fileuri: &str, path_mappings: &PathMappings) -> bool {
+        self.is_filtered(fileuri) || self.is_filtered(&path_mappings.local_uri(fileuri))
+    }
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // let the `*` consume one more character
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workspace::PathMapping;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*/vendor/*", "/app/vendor/symfony/Kernel.php"));
+        assert!(glob_match("**/vendor/**", "/app/vendor/autoload.php"));
+        assert!(glob_match("/app/src/?oo.php", "/app/src/foo.php"));
+        assert!(!glob_match("*/vendor/*", "/app/src/Controller.php"));
+        assert!(!glob_match("/app/src/?oo.php", "/app/src/fooo.php"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn test_is_filtered() {
+        let filters = StepFilters::new(vec!["*/vendor/*".to_string(), "*Proxy.php".to_string()]);
+        assert!(filters.is_filtered("file:///app/vendor/composer/ClassLoader.php"));
+        assert!(filters.is_filtered("file:///app/var/cache/ServiceProxy.php"));
+        assert!(!filters.is_filtered("file:///app/src/Kernel.php"));
+        assert!(!StepFilters::default().is_filtered("file:///app/vendor/autoload.php"));
+    }
+
+    #[test]
+    fn test_is_filtered_remote() {
+        let filters = StepFilters::new(vec!["/home/me/app/lib/*".to_string()]);
+        let path_mappings = PathMappings::new(vec![PathMapping {
+            remote: "/var/www".to_string(),
+            local: "/home/me/app".to_string(),
+        }]);
+        assert!(filters.is_filtered_remote("file:///var/www/lib/Lib.php", &path_mappings));
+        assert!(!filters.is_filtered_remote("file:///var/www/src/Kernel.php", &path_mappings));
+        assert!(!filters.is_filtered_remote("file:///var/www/lib/Lib.php", &PathMappings::default()));
+    }
+}
@@ -1,8 +1,11 @@
 
 use ratatui::style::Color;
 use ratatui::style::Style;
+use serde::Deserialize;
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Theme {
     Dark,
     SolarizedDark,
@@ -54,11 +57,17 @@ impl Theme {
                 pane_border_inactive: Style::default().fg(Solarized::Base02.to_color()),
                 source_line: Style::default().fg(Solarized::Base1.to_color()),
                 source_line_no: Style::default().fg(Solarized::Yellow.to_color()),
+                source_cursor: Style::default().fg(Solarized::Base03.to_color()).bg(Solarized::Yellow.to_color()),
+                source_breakpoint: Style::default().fg(Solarized::Red.to_color()),
                 source_line_highlight: Style::default().bg(Solarized::Base02.to_color()).fg(Solarized::Base3.to_color()),
                 source_annotation: Style::default().fg(Solarized::Magenta.to_color()),
                 source_annotation_historic: Style::default().fg(Solarized::Base01.to_color()),
                 stack_line: Style::default().fg(Solarized::Base1.to_color()),
 
+                diff_changed: Style::default().fg(Solarized::Yellow.to_color()),
+                diff_new: Style::default().fg(Solarized::Green.to_color()),
+                diff_removed: Style::default().fg(Solarized::Red.to_color()),
+
                 widget_active: Style::default().fg(Solarized::Base02.to_color()).bg(Solarized::Green.to_color()),
                 widget_inactive: Style::default().fg(Solarized::Base1.to_color()).bg(Solarized::Base03.to_color()),
                 widget_mode_debug: Style::default().fg(Solarized::Base1.to_color()).bg(Solarized::Base03.to_color()),
@@ -87,12 +96,18 @@ impl Theme {
 
                 source_line: Style::default().fg(Color::White),
                 source_line_no: Style::default().fg(Color::Yellow),
+                source_cursor: Style::default().fg(Color::Black).bg(Color::Yellow),
+                source_breakpoint: Style::default().fg(Color::Red),
                 source_line_highlight: Style::default().bg(Color::Blue),
                 source_annotation: Style::default().fg(Color::Cyan),
                 source_annotation_historic: Style::default().fg(Color::DarkGray),
 
                 stack_line: Style::default().fg(Color::White),
 
+                diff_changed: Style::default().fg(Color::LightYellow),
+                diff_new: Style::default().fg(Color::LightGreen),
+                diff_removed: Style::default().fg(Color::LightRed),
+
                 widget_active: Style::default().fg(Color::Black).bg(Color::Green),
                 widget_inactive: Style::default().fg(Color::Black).bg(Color::Yellow),
                 widget_mode_debug: Style::default().bg(Color::Blue),
@@ -124,12 +139,19 @@ pub struct Scheme {
 
     pub source_line: Style,
     pub source_line_no: Style,
+    pub source_cursor: Style,
+    pub source_breakpoint: Style,
     pub source_line_highlight: Style,
     pub source_annotation: Style,
     pub source_annotation_historic: Style,
 
     pub stack_line: Style,
 
+    /// values which changed since the previous step
+    pub diff_changed: Style,
+    pub diff_new: Style,
+    pub diff_removed: Style,
+
     pub widget_active: Style,
     pub widget_inactive: Style,
     pub widget_mode_debug: Style,
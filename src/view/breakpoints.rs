@@ -0,0 +1,332 @@
+use super::centered_dialog;
+use super::View;
+use crate::app::App;
+use crate::breakpoint::parse_hit;
+use crate::dbgp::client::Breakpoint;
+use crate::dbgp::client::BreakpointState;
+use crate::dbgp::client::BreakpointType;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use anyhow::Result;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+pub struct BreakpointDialog {}
+pub struct BreakpointsComponent {}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BreakpointField {
+    Target,
+    Condition,
+    Hit,
+}
+
+#[derive(Default)]
+pub struct BreakpointDialogState {
+    pub breakpoint: Breakpoint,
+    pub target: Input,
+    pub condition: Input,
+    pub hit: Input,
+    pub focus: usize,
+}
+
+impl BreakpointDialogState {
+    pub fn edit(breakpoint: Breakpoint) -> Self {
+        Self {
+            target: Input::new(
+                breakpoint
+                    .exception
+                    .clone()
+                    .or(breakpoint.function.clone())
+                    .unwrap_or_default(),
+            ),
+            condition: Input::new(breakpoint.expression.clone().unwrap_or_default()),
+            hit: Input::new(match (&breakpoint.hit_condition, breakpoint.hit_value) {
+                (Some(condition), Some(value)) => format!("{} {}", condition.as_str(), value),
+                _ => String::new(),
+            }),
+            breakpoint,
+            focus: 0,
+        }
+    }
+
+    pub fn fields(&self) -> Vec<BreakpointField> {
+        match self.breakpoint.breakpoint_type.is_line() {
+            true => vec![BreakpointField::Condition, BreakpointField::Hit],
+            false => vec![BreakpointField::Target, BreakpointField::Hit],
+        }
+    }
+
+    fn label(&self, field: BreakpointField) -> &str {
+        match field {
+            BreakpointField::Target => match self.breakpoint.breakpoint_type {
+                BreakpointType::Exception => "Exception",
+                _ => "Function",
+            },
+            BreakpointField::Condition => "Condition",
+            BreakpointField::Hit => "Hit",
+        }
+    }
+
+    fn input(&self, field: BreakpointField) -> &Input {
+        match field {
+            BreakpointField::Target => &self.target,
+            BreakpointField::Condition => &self.condition,
+            BreakpointField::Hit => &self.hit,
+        }
+    }
+
+    fn focused_field(&self) -> BreakpointField {
+        let fields = self.fields();
+        fields[self.focus % fields.len()]
+    }
+
+    fn focused_input_mut(&mut self) -> &mut Input {
+        match self.focused_field() {
+            BreakpointField::Target => &mut self.target,
+            BreakpointField::Condition => &mut self.condition,
+            BreakpointField::Hit => &mut self.hit,
+        }
+    }
+
+    /// build the breakpoint as described by the dialog inputs
+    pub fn breakpoint(&self) -> Result<Breakpoint> {
+        let mut breakpoint = self.breakpoint.clone();
+        // class names are matched without the leading namespace separator
+        let target = self.target.value().trim().trim_start_matches('\\');
+        match breakpoint.breakpoint_type {
+            BreakpointType::Exception | BreakpointType::Call | BreakpointType::Return => {
+                if target.is_empty() {
+                    anyhow::bail!("{} must not be empty", self.label(BreakpointField::Target));
+                }
+                match breakpoint.breakpoint_type {
+                    BreakpointType::Exception => breakpoint.exception = Some(target.to_string()),
+                    _ => breakpoint.function = Some(target.to_string()),
+                }
+            }
+            _ => (),
+        }
+        breakpoint.expression = match self.condition.value().trim() {
+            "" => None,
+            expression => Some(expression.to_string()),
+        };
+        if let BreakpointType::Line | BreakpointType::Conditional = breakpoint.breakpoint_type {
+            breakpoint.breakpoint_type = match breakpoint.expression {
+                Some(_) => BreakpointType::Conditional,
+                None => BreakpointType::Line,
+            };
+        }
+        (breakpoint.hit_condition, breakpoint.hit_value) = match parse_hit(self.hit.value())? {
+            Some((condition, value)) => (Some(condition), Some(value)),
+            None => (None, None),
+        };
+        Ok(breakpoint)
+    }
+}
+
+impl View for BreakpointDialog {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        match event {
+            AppEvent::Input(e) => {
+                let action = app.keymap().action(Scope::Dialog, &e);
+                let state = &mut app.session_view.breakpoint_dialog;
+                match action {
+                    Some(Action::DialogCancel) => return Some(AppEvent::BreakpointDialogCancel),
+                    Some(Action::DialogApply) => return Some(AppEvent::BreakpointDialogApply),
+                    Some(Action::DialogNextField) => {
+                        state.focus = (state.focus + 1) % state.fields().len()
+                    }
+                    Some(Action::DialogPreviousField) => {
+                        state.focus = (state.focus + state.fields().len() - 1) % state.fields().len()
+                    }
+                    _ => {
+                        state
+                            .focused_input_mut()
+                            .handle_event(&crossterm::event::Event::Key(e));
+                    }
+                };
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let state = &app.session_view.breakpoint_dialog;
+        let fields = state.fields();
+        let label_width = fields.iter().map(|f| state.label(*f).len()).max().unwrap_or(0) + 2;
+
+        let darea = centered_dialog(fields.len() as u16 + 2, area);
+        frame.render_widget(Clear, darea);
+
+        let lines: Vec<Line> = fields
+            .iter()
+            .map(|field| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}", format!("{}:", state.label(*field)), width = label_width),
+                        app.theme().syntax_label,
+                    ),
+                    Span::raw(state.input(*field).value()).style(app.theme().text_input),
+                ])
+            })
+            .collect();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::all())
+                    .title(format!(
+                        "Breakpoint {} [{}] next field",
+                        breakpoint_label(&state.breakpoint),
+                        app.keymap().keys(Action::DialogNextField)
+                    ))
+                    .title_bottom("Hit: >= n, == n or % n")
+                    .style(app.theme().pane_border_active),
+            ),
+            darea,
+        );
+
+        let input = state.input(state.focused_field());
+        let width = darea.width.saturating_sub(label_width as u16 + 2).max(3);
+        let scroll = input.visual_scroll(width as usize);
+        let x = input.visual_cursor().max(scroll) - scroll + 1 + label_width;
+        frame.set_cursor_position((
+            darea.x + x as u16,
+            darea.y + 1 + (state.focus % fields.len()) as u16,
+        ));
+    }
+}
+
+impl View for BreakpointsComponent {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        match event {
+            AppEvent::Scroll(amount) => Some(AppEvent::ScrollBreakpoints(amount)),
+            AppEvent::Input(e) => match app.keymap().action(Scope::Breakpoints, &e) {
+                Some(Action::BreakpointToggleState) => Some(AppEvent::BreakpointToggleState),
+                Some(Action::BreakpointDelete) => Some(AppEvent::BreakpointDelete),
+                Some(Action::BreakpointJump) => Some(AppEvent::BreakpointJump),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let selected = app.session_view.breakpoints_scroll.0 as usize;
+        let mut lines: Vec<Line> = Vec::new();
+
+        for (index, breakpoint) in app.breakpoints.breakpoints.iter().enumerate() {
+            lines.push(
+                Line::from(vec![
+                    Span::styled(
+                        match breakpoint.state {
+                            BreakpointState::Enabled => "● ",
+                            BreakpointState::Disabled => "○ ",
+                        },
+                        app.theme().source_breakpoint,
+                    ),
+                    Span::styled(
+                        format!("{:<12}", breakpoint.breakpoint_type.as_str()),
+                        app.theme().syntax_type,
+                    ),
+                    Span::raw(breakpoint_label(breakpoint)),
+                ])
+                .style(match (index == selected, &breakpoint.state) {
+                    (true, _) => app.theme().source_line_highlight,
+                    (false, BreakpointState::Disabled) => app.theme().source_annotation_historic,
+                    (false, BreakpointState::Enabled) => app.theme().source_line,
+                }),
+            );
+        }
+
+        let y_scroll = (selected as u16 + 1).saturating_sub(area.height);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((y_scroll, app.session_view.breakpoints_scroll.1)),
+            area,
+        );
+    }
+}
+
+/// describe the location and conditions of a breakpoint
+pub fn breakpoint_label(breakpoint: &Breakpoint) -> String {
+    let mut parts = vec![];
+    if let Some(exception) = &breakpoint.exception {
+        parts.push(format!("exception {}", exception));
+    }
+    if let Some(function) = &breakpoint.function {
+        parts.push(format!("{} {}", breakpoint.breakpoint_type.as_str(), function));
+    }
+    if let (Some(filename), Some(line_no)) = (&breakpoint.filename, breakpoint.line_no) {
+        parts.push(format!(
+            "{}:{}",
+            filename.rsplit('/').next().unwrap_or(filename),
+            line_no
+        ));
+    }
+    if let Some(expression) = &breakpoint.expression {
+        parts.push(format!("if {}", expression));
+    }
+    if let (Some(condition), Some(value)) = (&breakpoint.hit_condition, breakpoint.hit_value) {
+        parts.push(format!("hit {} {}", condition.as_str(), value));
+    }
+    if let Some(hit_count) = breakpoint.hit_count {
+        parts.push(format!("hits: {}", hit_count));
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dbgp::client::HitCondition;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_dialog_builds_conditional_breakpoint() -> Result<()> {
+        let mut state =
+            BreakpointDialogState::edit(Breakpoint::line("file:///app/foo.php".to_string(), 12));
+        state.condition = Input::new("$i > 100".to_string());
+        state.hit = Input::new("% 2".to_string());
+
+        let breakpoint = state.breakpoint()?;
+        assert_eq!(BreakpointType::Conditional, breakpoint.breakpoint_type);
+        assert_eq!(Some("$i > 100".to_string()), breakpoint.expression);
+        assert_eq!(Some(HitCondition::Multiple), breakpoint.hit_condition);
+        assert_eq!(Some(2), breakpoint.hit_value);
+        assert_eq!("foo.php:12, if $i > 100, hit % 2", breakpoint_label(&breakpoint));
+
+        state.condition = Input::default();
+        state.hit = Input::default();
+        let breakpoint = state.breakpoint()?;
+        assert_eq!(BreakpointType::Line, breakpoint.breakpoint_type);
+        assert_eq!(None, breakpoint.hit_condition);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dialog_builds_exception_breakpoint() -> Result<()> {
+        let mut state = BreakpointDialogState::edit(Breakpoint {
+            breakpoint_type: BreakpointType::Exception,
+            ..Default::default()
+        });
+        assert_eq!(vec![BreakpointField::Target, BreakpointField::Hit], state.fields());
+        assert!(state.breakpoint().is_err());
+
+        state.target = Input::new("\\Doctrine\\DBAL\\Exception".to_string());
+        let breakpoint = state.breakpoint()?;
+        assert_eq!(Some("Doctrine\\DBAL\\Exception".to_string()), breakpoint.exception);
+        assert_eq!(BreakpointType::Exception, breakpoint.breakpoint_type);
+        assert_eq!("exception Doctrine\\DBAL\\Exception", breakpoint_label(&breakpoint));
+        Ok(())
+    }
+}
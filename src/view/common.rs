@@ -0,0 +1,60 @@
+use super::centered_dialog;
+use crate::app::App;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+/// handle an event of a dialog with a single input, keys which do not apply
+/// or cancel the dialog are sent to the input
+pub fn handle_input_dialog(
+    app: &mut App,
+    event: AppEvent,
+    input: fn(&mut App) -> &mut Input,
+    apply: AppEvent,
+    cancel: AppEvent,
+) -> Option<AppEvent> {
+    let key_event = match event {
+        AppEvent::Input(key_event) => key_event,
+        _ => return None,
+    };
+    match app.keymap().action(Scope::Dialog, &key_event) {
+        Some(Action::DialogApply) => return Some(apply),
+        Some(Action::DialogCancel) => return Some(cancel),
+        _ => (),
+    }
+    input(app).handle_event(&crossterm::event::Event::Key(key_event));
+    None
+}
+
+/// draw a dialog with a single input centered in the area
+pub fn draw_input_dialog(app: &App, frame: &mut Frame, area: Rect, title: String, input: &Input) {
+    let darea = centered_dialog(3, area);
+    frame.render_widget(Clear, darea);
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::raw(input.value()).style(app.theme().text_input)
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::all())
+                .title(title)
+                .style(app.theme().pane_border_active),
+        ),
+        darea,
+    );
+
+    let width = darea.width.max(3);
+    let scroll = input.visual_scroll(width as usize);
+    let x = input.visual_cursor().max(scroll) - scroll + 1;
+    frame.set_cursor_position((darea.x + x as u16, darea.y + 1));
+}
@@ -1,8 +1,21 @@
+use super::common::draw_input_dialog;
+use super::common::handle_input_dialog;
 use super::properties::draw_properties;
+use super::properties::draw_removed_properties;
+use super::properties::header_row;
+use super::properties::parent_row;
+use super::properties::sibling_row;
+use super::properties::PropertyRow;
+use super::properties::PropertyTree;
 use super::View;
 use crate::app::App;
+use crate::dbgp::client::Properties;
+use crate::dbgp::client::Property;
+use crate::dbgp::client::PropertyType;
 use crate::event::input::AppEvent;
-use crossterm::event::KeyCode;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use anyhow::Result;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Rect;
@@ -11,20 +24,69 @@ use ratatui::text::Span;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
 use ratatui::widgets::Paragraph;
+use ratatui::widgets::Tabs;
 use ratatui::Frame;
 use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
 
 pub struct ContextComponent {}
+pub struct PropertyDialog {}
+
+/// the scalar property being edited
+#[derive(Default)]
+pub struct PropertyDialogState {
+    pub fullname: String,
+    pub property_type: PropertyType,
+    pub input: Input,
+}
+
+impl PropertyDialogState {
+    pub fn edit(property: &Property) -> Result<Self> {
+        if !property.property_type.is_scalar() {
+            anyhow::bail!(
+                "Only scalar values can be edited, {} is {}",
+                property.fullname,
+                property.property_type
+            );
+        }
+        Ok(Self {
+            fullname: property.fullname.clone(),
+            property_type: property.property_type,
+            input: Input::new(property.value.clone().unwrap_or_default()),
+        })
+    }
+
+    /// the value to send to the engine, validated against the type
+    pub fn value(&self) -> Result<String> {
+        let value = self.input.value();
+        match self.property_type {
+            PropertyType::Int => {
+                value.trim().parse::<i64>()?;
+                Ok(value.trim().to_string())
+            }
+            PropertyType::Float => {
+                value.trim().parse::<f64>()?;
+                Ok(value.trim().to_string())
+            }
+            // the engine casts the value, so "false" would be true
+            PropertyType::Bool => match value.trim().to_lowercase().as_str() {
+                "1" | "true" => Ok("1".to_string()),
+                "0" | "false" => Ok("0".to_string()),
+                _ => anyhow::bail!("Expected true or false, got \"{}\"", value),
+            },
+            _ => Ok(value.to_string()),
+        }
+    }
+}
 
 impl View for ContextComponent {
     fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
         if app.session_view.context_filter.show {
             return match event {
                 AppEvent::Input(e) => {
-                    if e.code == KeyCode::Esc {
-                        return Some(AppEvent::ContextSearchClose);
-                    }
-                    if e.code == KeyCode::Enter {
+                    if let Some(Action::DialogCancel | Action::DialogApply) =
+                        app.keymap().action(Scope::Dialog, &e)
+                    {
                         return Some(AppEvent::ContextSearchClose);
                     }
                     app.session_view.context_filter.input.handle_event(&crossterm::event::Event::Key(e));
@@ -36,8 +98,55 @@ impl View for ContextComponent {
         match event {
             AppEvent::Scroll(scroll) => Some(AppEvent::ScrollContext(scroll)),
             AppEvent::Input(e) => {
-                match e.code {
-                    KeyCode::Char('f') => Some(AppEvent::ContextFilterOpen),
+                match app.keymap().action(Scope::Context, &e) {
+                    Some(Action::ContextFilter) => Some(AppEvent::ContextFilterOpen),
+                    Some(Action::ContextSwitch) => Some(AppEvent::ContextSwitch),
+                    Some(Action::ContextEdit) => Some(AppEvent::PropertyEdit),
+                    Some(Action::ContextToggle) => {
+                        let (fullname, fetch) = match selected_property(app) {
+                            Some(p) => (p.fullname.clone(), p.children.is_empty() && p.has_children()),
+                            None => return None,
+                        };
+                        if fetch {
+                            return Some(AppEvent::ContextFetchChildren(fullname));
+                        }
+                        // keep the cursor on the property when collapsing from its closing brace
+                        let cursor = app.session_view.context_scroll.0 as usize;
+                        if let Some(header) = header_row(&context_rows(app), cursor) {
+                            app.session_view.context_scroll.0 = header as u16;
+                        }
+                        let collapsed = &mut app.session_view.context_collapsed;
+                        if !collapsed.remove(&fullname) {
+                            collapsed.insert(fullname);
+                        }
+                        None
+                    }
+                    Some(Action::ContextParent) => {
+                        select_row(app, parent_row);
+                        None
+                    }
+                    Some(Action::ContextNextSibling) => {
+                        select_row(app, |rows, index| sibling_row(rows, index, true));
+                        None
+                    }
+                    Some(Action::ContextPreviousSibling) => {
+                        select_row(app, |rows, index| sibling_row(rows, index, false));
+                        None
+                    }
+                    Some(Action::ContextCopyName) => {
+                        let fullname = selected_property(app)?.fullname.clone();
+                        app.session_view.eval_state.input = Input::new(fullname);
+                        Some(AppEvent::EvalStart)
+                    }
+                    Some(Action::ContextTimeline) => {
+                        Some(AppEvent::Timeline(selected_property(app)?.fullname.clone()))
+                    }
+                    Some(Action::ContextMore) => match selected_property(app) {
+                        Some(p) if p.remaining_children() > 0 => {
+                            Some(AppEvent::ContextFetchChildren(p.fullname.clone()))
+                        }
+                        _ => None,
+                    },
                     _ => None,
                 }
             },
@@ -46,22 +155,41 @@ impl View for ContextComponent {
     }
 
     fn draw(app: &App, frame: &mut Frame, area: Rect) {
-        let entry = match app.history.current() {
-            Some(e) => e,
-            None => return,
-        };
-        let context = match entry.stack(app.session_view.stack_depth()) {
-            Some(stack) => match &stack.context {
-                Some(context) => context,
-                None => return,
-            },
+        let context_names = match app.session() {
+            Some(session) => &session.context_names,
             None => return,
         };
         let layout = Layout::default()
-            .constraints([Constraint::Length(
-                if app.session_view.context_filter.show { 3 } else { 0 }
-            ), Constraint::Min(1)]);
-        let areas = layout.split(area);
+            .constraints([
+                Constraint::Length(if context_names.len() > 1 { 1 } else { 0 }),
+                Constraint::Length(if app.session_view.context_filter.show { 3 } else { 0 }),
+                Constraint::Min(1),
+            ]);
+        let [tabs_area, filter_area, properties_area] = layout.areas(area);
+
+        frame.render_widget(
+            Tabs::new(context_names.iter().map(|n| n.name.as_str()))
+                .select(
+                    context_names
+                        .iter()
+                        .position(|n| n.id == app.session_view.context_id),
+                )
+                .style(app.theme().widget_inactive)
+                .highlight_style(app.theme().widget_active),
+            tabs_area,
+        );
+
+        let properties = match context_properties(app) {
+            Some(properties) => properties,
+            None => {
+                frame.render_widget(
+                    Paragraph::new("not fetched for this step").style(app.theme().source_annotation_historic),
+                    properties_area,
+                );
+                return;
+            }
+        };
+        let areas = [filter_area, properties_area];
 
         frame.render_widget(Paragraph::new(Line::from(vec![
             Span::raw(app.session_view.context_filter.input.value()).style(app.theme().text_input),
@@ -75,24 +203,127 @@ impl View for ContextComponent {
             frame.set_cursor_position((area.x + x as u16, area.y + 1));
         }
             
-        let mut filter_path = app.session_view.context_filter.segments().clone();
-
+        // the cursor is kept on the last line when scrolling down
+        let cursor = app.session_view.context_scroll.0 as u32;
+        let truncate_from = (cursor + 1).saturating_sub(areas[1].height as u32);
+        let changes = app.session().and_then(|session| {
+            session
+                .history
+                .changes(app.session_view.stack_depth(), app.session_view.context_id)
+        });
         let mut lines: Vec<Line> = vec![];
-        let truncate_from = app.session_view.context_scroll.0 as u32;
-        draw_properties(
-            &app.theme(),
-            context.properties.defined_properties(),
-            &mut lines,
-            0,
-            &mut filter_path,
-            &truncate_from,
-            &mut 0
-        );
+        let mut tree = PropertyTree {
+            filter_path: app.session_view.context_filter.segments(),
+            truncate_until: truncate_from,
+            collapsed: Some(&app.session_view.context_collapsed),
+            changes: changes.as_deref(),
+            ..Default::default()
+        };
+        draw_properties(&app.theme(), properties.defined_properties(), &mut lines, 0, &mut tree);
+        draw_removed_properties(&app.theme(), &mut lines, &mut tree);
 
+        if let Some(line) = lines.get_mut((cursor - truncate_from) as usize) {
+            *line = line.clone().patch_style(app.theme().source_line_highlight);
+        }
 
         frame.render_widget(
-            Paragraph::new(lines.clone()).scroll((0, app.session_view.context_scroll.1)),
+            Paragraph::new(lines).scroll((0, app.session_view.context_scroll.1)),
             areas[1],
         );
     }
 }
+
+impl View for PropertyDialog {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        handle_input_dialog(
+            app,
+            event,
+            |app| &mut app.session_view.property_dialog.input,
+            AppEvent::PropertyDialogApply,
+            AppEvent::PropertyDialogCancel,
+        )
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let state = &app.session_view.property_dialog;
+        draw_input_dialog(
+            app,
+            frame,
+            area,
+            format!("Set {} ({})", state.fullname, state.property_type),
+            &state.input,
+        );
+    }
+}
+
+/// the properties of the selected stack frame
+fn context_properties(app: &App) -> Option<&Properties> {
+    let stack = app.history_entry()?.stack(app.session_view.stack_depth())?;
+    stack
+        .context(app.session_view.context_id)
+        .map(|context| &context.properties)
+}
+
+/// the property on each line of the context pane
+pub fn context_rows(app: &App) -> Vec<PropertyRow<'_>> {
+    let properties = match context_properties(app) {
+        Some(properties) => properties,
+        None => return vec![],
+    };
+    let mut tree = PropertyTree {
+        filter_path: app.session_view.context_filter.segments(),
+        truncate_until: u32::MAX,
+        collapsed: Some(&app.session_view.context_collapsed),
+        ..Default::default()
+    };
+    draw_properties(&app.theme(), properties.defined_properties(), &mut vec![], 0, &mut tree);
+    tree.rows
+}
+
+pub fn selected_property(app: &App) -> Option<&Property> {
+    context_rows(app)
+        .get(app.session_view.context_scroll.0 as usize)
+        .map(|row| row.property)
+}
+
+/// move the cursor to the row found by the given function
+fn select_row(app: &mut App, find: impl Fn(&[PropertyRow], usize) -> Option<usize>) {
+    let cursor = app.session_view.context_scroll.0 as usize;
+    if let Some(index) = find(&context_rows(app), cursor) {
+        app.session_view.context_scroll.0 = index as u16;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn state(property_type: PropertyType, value: &str) -> PropertyDialogState {
+        PropertyDialogState {
+            fullname: "$foo".to_string(),
+            property_type,
+            input: Input::new(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_property_dialog_value() {
+        assert_eq!("12", state(PropertyType::Int, " 12 ").value().unwrap());
+        assert!(state(PropertyType::Int, "12.5").value().is_err());
+        assert_eq!("12.5", state(PropertyType::Float, "12.5").value().unwrap());
+        assert_eq!("0", state(PropertyType::Bool, "False").value().unwrap());
+        assert_eq!("1", state(PropertyType::Bool, "1").value().unwrap());
+        assert!(state(PropertyType::Bool, "yes").value().is_err());
+        assert_eq!(" spaced ", state(PropertyType::String, " spaced ").value().unwrap());
+    }
+
+    #[test]
+    fn test_property_dialog_edit_scalars_only() {
+        let property = Property {
+            property_type: PropertyType::Array,
+            ..Default::default()
+        };
+        assert!(PropertyDialogState::edit(&property).is_err());
+    }
+}
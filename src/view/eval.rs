@@ -1,4 +1,5 @@
-use super::centered_rect_absolute;
+use super::common::draw_input_dialog;
+use super::common::handle_input_dialog;
 use super::View;
 use crate::app::App;
 use crate::dbgp::client::EvalResponse;
@@ -6,16 +7,11 @@ use crate::dbgp::client::Property;
 use crate::dbgp::client::PropertyType;
 use crate::event::input::AppEvent;
 use crate::theme::Scheme;
-use crossterm::event::KeyCode;
 use ratatui::layout::Rect;
 use ratatui::text::Line;
 use ratatui::text::Span;
-use ratatui::widgets::Block;
-use ratatui::widgets::Borders;
-use ratatui::widgets::Clear;
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
-use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 
 pub struct EvalComponent {}
@@ -37,7 +33,7 @@ impl View for EvalComponent {
     }
 
     fn draw(app: &App, frame: &mut Frame, area: Rect) {
-        if let Some(entry) = &app.history.current() {
+        if let Some(entry) = app.history_entry() {
             if let Some(eval_entry) = &entry.eval {
                 if let Some(error) = &eval_entry.response.error {
                     frame.render_widget(
@@ -65,56 +61,23 @@ impl View for EvalComponent {
 
 impl View for EvalDialog {
     fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
-        match event {
-            AppEvent::Input(e) => {
-                if e.code == KeyCode::Esc {
-                    return Some(AppEvent::EvalCancel);
-                }
-                if e.code == KeyCode::Enter {
-                    return Some(AppEvent::EvalExecute);
-                }
-                app.session_view
-                    .eval_state
-                    .input
-                    .handle_event(&crossterm::event::Event::Key(e));
-                None
-            }
-            _ => None,
-        }
+        handle_input_dialog(
+            app,
+            event,
+            |app| &mut app.session_view.eval_state.input,
+            AppEvent::EvalExecute,
+            AppEvent::EvalCancel,
+        )
     }
 
     fn draw(app: &App, frame: &mut Frame, area: Rect) {
-        let darea = centered_rect_absolute(area.width - 10, 3, area);
-        frame.render_widget(Clear, darea);
-        frame.render_widget(
-            Paragraph::new(Line::from(vec![Span::raw(
-                app.session_view.eval_state.input.value(),
-            )
-            .style(app.theme().text_input)]))
-            .block(
-                Block::default()
-                    .borders(Borders::all())
-                    .title("Enter expression")
-                    .style(app.theme().pane_border_active),
-            ),
-            darea,
+        draw_input_dialog(
+            app,
+            frame,
+            area,
+            "Enter expression".to_string(),
+            &app.session_view.eval_state.input,
         );
-
-        let width = darea.width.max(3);
-        let scroll = app
-            .session_view
-            .eval_state
-            .input
-            .visual_scroll(width as usize);
-        let x = app
-            .session_view
-            .eval_state
-            .input
-            .visual_cursor()
-            .max(scroll)
-            - scroll
-            + 1;
-        frame.set_cursor_position((darea.x + x as u16, darea.y + 1));
     }
 }
 
@@ -207,8 +170,10 @@ mod test {
     fn test_draw_properties_two_levels() -> Result<()> {
         let mut lines = vec![];
         let mut prop1 = Property::default();
-        let mut prop2 = Property::default();
-        prop2.name = "bar".to_string();
+        let prop2 = Property {
+            name: "bar".to_string(),
+            ..Default::default()
+        };
         prop1.children = Properties::from_properties(vec![prop2]);
         prop1.name = "foo".to_string();
 
@@ -233,10 +198,12 @@ mod test {
     fn test_filter_property_multiple_level() -> Result<()> {
         let mut lines = vec![];
         let mut prop1 = Property::default();
-        let mut prop2 = Property::default();
+        let prop2 = Property {
+            name: "bar".to_string(),
+            ..Default::default()
+        };
         let prop3 = Property::default();
 
-        prop2.name = "bar".to_string();
         prop1.children = Properties::from_properties(vec![prop2]);
         prop1.name = "foo".to_string();
 
@@ -1,6 +1,8 @@
 use super::View;
-use crate::app::{App, ListenStatus, SelectedView};
+use crate::app::{App, SelectedView};
 use crate::event::input::AppEvent;
+use crate::keymap::Keymap;
+use crate::keymap::Scope;
 use ratatui::layout::Rect;
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
@@ -11,7 +13,7 @@ impl View for HelpView {
     fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
         match event {
             AppEvent::Input(_) => {
-                if app.listening_status == ListenStatus::Connected{
+                if app.session().is_some() {
                     Some(AppEvent::ChangeView(SelectedView::Session))
                 } else {
                     Some(AppEvent::Listen)
@@ -21,46 +23,58 @@ impl View for HelpView {
         }
     }
 
-    fn draw(_app: &App, frame: &mut Frame, area: Rect) {
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
 
-        frame.render_widget(Paragraph::new(help()), area);
+        frame.render_widget(Paragraph::new(help(app.keymap())), area);
 
     }
 }
 
-fn help() -> String {
-"
+fn help(keymap: &Keymap) -> String {
+    let mut help = String::from("
 Help for you - press any key to return.
 
-Key mappings (prefix with number to repeat):
+Key mappings (prefix with number to repeat, ctrl-c to quit):
+");
 
-[r]     run
-[n]     next / step into
-[N]     step over
-[p]     previous (switches to history mode if in current mode)
-[o]     step out
-[d]     disconnect
-[e]     enter an expression
-[j]     scroll down
-[J]     scroll down 10
-[k]     scroll up
-[K]     scroll up 10
-[h]     scroll left
-[H]     scroll left 10
-[l]     scroll right
-[L]     scroll right 10
-[+]     increase context depth
-[-]     decrease context depth
-[t]     rotate the theme
-[f]     Filter (context pane) - use dot notation to filter on multiple levels.
-[enter] toggle pane focus (full screen)
+    let mut scope: Option<Scope> = None;
+    for (action, _) in keymap.bindings.iter() {
+        if scope != Some(action.scope()) {
+            scope = Some(action.scope());
+            help.push_str(&format!("\n{}:\n\n", action.scope().title()));
+        }
+        help.push_str(&format!(
+            "{:<16} {:<24} {}\n",
+            format!("[{}]", keymap.keys(*action)),
+            action.name(),
+            action.description()
+        ));
+    }
 
+    help.push_str("
 Legend:
 
 󱘖 : Connection status
- : Stack depth
- : Iteration number
-".to_string()
+ : Stack depth
+ : Iteration number
+");
+    help
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
 
+    #[test]
+    fn test_help_shows_remapped_keys() -> anyhow::Result<()> {
+        let keymap = Keymap::with_overrides(&HashMap::from([(
+            "step_into".to_string(),
+            vec!["ctrl-n".to_string()],
+        )]))?;
+        let help = help(&keymap);
+        assert!(help.contains("[ctrl-n]         step_into"));
+        assert!(help.contains("[j, down]        scroll.down"));
+        Ok(())
+    }
+}
@@ -1,14 +1,20 @@
+use super::breakpoints::BreakpointDialog;
+use super::context::PropertyDialog;
 use super::eval::EvalDialog;
 use super::help::HelpView;
 use super::listen::ListenView;
 use super::session::SessionView;
 use super::session::SessionViewMode;
+use super::sessions::SessionsView;
+use super::step_until::StepUntilDialog;
+use super::timeline::TimelineView;
+use super::watches::WatchDialog;
 use super::View;
 use crate::app::ActiveDialog;
 use crate::app::App;
-use crate::app::ListenStatus;
 use crate::app::SelectedView;
 use crate::event::input::AppEvent;
+use crate::keymap::Action;
 use crate::notification::NotificationLevel;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
@@ -42,12 +48,18 @@ impl View for LayoutView {
         match app.view_current {
             SelectedView::Listen => ListenView::draw(app, f, rows[1]),
             SelectedView::Session => SessionView::draw(app, f, rows[1]),
+            SelectedView::Sessions => SessionsView::draw(app, f, rows[1]),
             SelectedView::Help => HelpView::draw(app, f, rows[1]),
+            SelectedView::Timeline => TimelineView::draw(app, f, rows[1]),
         }
 
         if let Some(dialog) = &app.active_dialog {
             match &dialog {
                 ActiveDialog::Eval => EvalDialog::draw(app, f, area),
+                ActiveDialog::Breakpoint => BreakpointDialog::draw(app, f, area),
+                ActiveDialog::Property => PropertyDialog::draw(app, f, area),
+                ActiveDialog::Watch => WatchDialog::draw(app, f, area),
+                ActiveDialog::StepUntil => StepUntilDialog::draw(app, f, area),
             }
         }
     }
@@ -74,18 +86,28 @@ fn notification_widget(app: &App) -> Paragraph<'_> {
 }
 
 fn status_widget<'a>(app: &'a App) -> Paragraph<'a> {
+    let session = app.session();
     Paragraph::new(vec![Line::from(vec![
         Span::styled(
             format!(
                 " 󱘖 {} ",
-                match app.listening_status {
-                    ListenStatus::Connected => "connected".to_string(),
-
-                    ListenStatus::Listening => app.config.listen.to_string(),
-                    ListenStatus::Refusing => "refusing".to_string(),
+                match (session, &app.view_current) {
+                    (None, _) | (_, SelectedView::Listen) => app.config.listen.to_string(),
+                    (Some(session), _) => format!(
+                        "{} {}{}",
+                        session.label(),
+                        match session.is_connected() {
+                            true => "connected",
+                            false => "disconnected",
+                        },
+                        match app.sessions.len() {
+                            1 => String::new(),
+                            count => format!(" ({} sessions)", count),
+                        }
+                    ),
                 },
             ),
-            match app.listening_status.is_connected() {
+            match session.is_some_and(|s| s.is_connected()) {
                 false => app.theme().widget_inactive,
                 true => app.theme().widget_active,
             },
@@ -93,30 +115,36 @@ fn status_widget<'a>(app: &'a App) -> Paragraph<'a> {
         Span::styled(
             format!(
                 "   {:<3} ",
-                app.history.current().map_or("n/a".to_string(), |entry| {
+                app.history_entry().map_or("n/a".to_string(), |entry| {
                     entry.stacks.len().to_string()
                 })
             ),
             app.theme().widget_inactive,
         ),
         Span::styled(
-            (match app.session_view.mode {
-                SessionViewMode::Current => match app.listening_status.is_connected() {
-                    true => format!("   {} / ∞", app.history.offset + 1),
+            (match (&app.session_view.mode, session) {
+                (_, None) => String::new(),
+                (SessionViewMode::Current, Some(session)) => match session.is_connected() {
+                    true => format!("   {} / ∞", session.history.offset + 1),
                     false => "   0 / 0".to_string(),
                 },
-                SessionViewMode::History => match app.listening_status {
-                    ListenStatus::Connected => format!(
-                        "   {} / {} history [p] to go back [n] to go forwards [b] to return",
-                        app.history.offset + 1,
-                        app.history.len()
+                (SessionViewMode::History, Some(session)) => match session.is_connected() {
+                    true => format!(
+                        "   {} / {} history [{}] to go back [{}] to go forwards [{}] to return",
+                        session.history.offset + 1,
+                        session.history.len(),
+                        app.keymap().keys(Action::HistoryPrevious),
+                        app.keymap().keys(Action::HistoryNext),
+                        app.keymap().keys(Action::HistoryExit),
                     ),
-                    ListenStatus::Refusing => format!(
-                        "   {} / {} disconnected [p] to go back [n] to go forwards [b] to listen",
-                        app.history.offset + 1,
-                        app.history.len()
+                    false => format!(
+                        "   {} / {} disconnected [{}] to go back [{}] to go forwards [{}] to listen",
+                        session.history.offset + 1,
+                        session.history.len(),
+                        app.keymap().keys(Action::HistoryPrevious),
+                        app.keymap().keys(Action::HistoryNext),
+                        app.keymap().keys(Action::HistoryExit),
                     ),
-                    ListenStatus::Listening => String::new(),
                 },
             })
             .to_string(),
@@ -125,5 +153,39 @@ fn status_widget<'a>(app: &'a App) -> Paragraph<'a> {
                 SessionViewMode::History => app.theme().widget_mode_history,
             },
         ),
+        match session.and_then(|s| s.running_since.map(|since| (since, s.step_until.as_ref()))) {
+            Some((_, Some(progress))) => Span::styled(
+                format!(
+                    "  step {}/{} until {} [{}] to interrupt ",
+                    progress.steps,
+                    progress.max_steps,
+                    progress.expression,
+                    app.keymap().keys(Action::Interrupt)
+                ),
+                app.theme().widget_active,
+            ),
+            Some((since, None)) => Span::styled(
+                format!(
+                    "  running {}s [{}] to interrupt ",
+                    since.elapsed().as_secs(),
+                    app.keymap().keys(Action::Interrupt)
+                ),
+                app.theme().widget_active,
+            ),
+            None => Span::styled(
+                match session.and_then(|s| s.server_status.as_ref()) {
+                    Some(status) => format!("  {} ", status),
+                    None => String::new(),
+                },
+                app.theme().widget_inactive,
+            ),
+        },
+        Span::styled(
+            match session.and_then(|s| s.server_exception.as_ref()) {
+                Some(exception) => format!(" {}: {} ", exception.class, exception.message),
+                None => String::new(),
+            },
+            app.theme().notification_error,
+        ),
     ])])
 }
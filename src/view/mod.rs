@@ -1,9 +1,15 @@
 pub mod layout;
 pub mod listen;
 pub mod session;
+pub mod sessions;
 pub mod stack;
 pub mod help;
 pub mod eval;
+pub mod breakpoints;
+pub mod output;
+pub mod watches;
+pub mod step_until;
+pub mod timeline;
 pub mod common;
 pub mod properties;
 
@@ -14,27 +20,33 @@ use crate::app::App;
 use crate::event::input::AppEvent;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::Frame;
+use serde::Deserialize;
 
 pub trait View {
     fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent>;
     fn draw(app: &App, frame: &mut Frame, area: Rect);
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ComponentType {
     Source,
     Context,
     Eval,
     Stack,
+    Breakpoints,
+    Output,
+    Watches,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Col {
     Left,
     Right,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Pane {
     pub component_type: ComponentType,
     pub constraint: Constraint,
@@ -51,3 +63,20 @@ pub fn centered_rect_absolute(width: u16, height: u16, r: Rect) -> Rect {
         height.min(r.height),
     )
 }
+
+/// a dialog of the given height spanning the area less a margin
+pub fn centered_dialog(height: u16, r: Rect) -> Rect {
+    centered_rect_absolute(r.width.saturating_sub(10), height, r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_centered_dialog() {
+        assert_eq!(Rect::new(5, 4, 70, 3), centered_dialog(3, Rect::new(0, 0, 80, 11)));
+        assert_eq!(Rect::new(2, 0, 0, 2), centered_dialog(3, Rect::new(0, 0, 4, 2)));
+    }
+}
@@ -0,0 +1,151 @@
+use super::View;
+use crate::app::App;
+use crate::app::OutputChunk;
+use crate::dbgp::client::StreamType;
+use crate::event::input::AppEvent;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+pub struct OutputComponent {}
+
+/// a line of output and the step which produced it
+#[derive(Debug, PartialEq)]
+pub struct OutputLine {
+    pub step: usize,
+    pub stream_type: StreamType,
+    pub text: String,
+}
+
+impl View for OutputComponent {
+    fn handle(_: &mut App, event: AppEvent) -> Option<AppEvent> {
+        match event {
+            AppEvent::Scroll(amount) => Some(AppEvent::ScrollOutput(amount)),
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let session = match app.session() {
+            Some(session) => session,
+            None => return,
+        };
+        let offset = session.history.offset;
+        let output = output_lines(&session.output);
+
+        let mut lines: Vec<Line> = Vec::new();
+        let mut previous_step = None;
+        for line in output.iter() {
+            let step = match previous_step == Some(line.step) {
+                true => String::new(),
+                false => (line.step + 1).to_string(),
+            };
+            previous_step = Some(line.step);
+            lines.push(
+                Line::from(vec![
+                    Span::styled(format!("{:>4} ", step), app.theme().source_line_no),
+                    Span::styled(
+                        line.text.clone(),
+                        match line.stream_type {
+                            StreamType::Stdout => app.theme().source_line,
+                            StreamType::Stderr => app.theme().notification_error,
+                        },
+                    ),
+                ])
+                .style(match line.step {
+                    step if step == offset => app.theme().source_line_highlight,
+                    // output which was produced after the step shown in history mode
+                    step if step > offset => app.theme().source_annotation_historic,
+                    _ => app.theme().source_line,
+                }),
+            );
+        }
+
+        // keep the output of the current step in view, scrolling moves up from there
+        let end = output.iter().take_while(|l| l.step <= offset).count() as u16;
+        let y_scroll = end
+            .saturating_sub(area.height)
+            .saturating_sub(app.session_view.output_scroll.0);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((y_scroll, app.session_view.output_scroll.1)),
+            area,
+        );
+    }
+}
+
+/// split the output into lines, a line can be written by more than one
+/// chunk in which case it belongs to the step which started it
+pub fn output_lines(chunks: &[OutputChunk]) -> Vec<OutputLine> {
+    let mut lines: Vec<OutputLine> = Vec::new();
+    let mut open = false;
+    for chunk in chunks {
+        for (index, text) in chunk.stream.data.split('\n').enumerate() {
+            if index == 0 {
+                if let Some(last) = lines.last_mut() {
+                    if open && last.stream_type == chunk.stream.stream_type {
+                        last.text.push_str(text);
+                        continue;
+                    }
+                }
+            }
+            lines.push(OutputLine {
+                step: chunk.step,
+                stream_type: chunk.stream.stream_type,
+                text: text.to_string(),
+            });
+        }
+        open = !chunk.stream.data.ends_with('\n');
+        // the trailing empty line is opened by the next chunk
+        if !open && lines.last().is_some_and(|l| l.text.is_empty()) {
+            lines.pop();
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dbgp::client::Stream;
+    use pretty_assertions::assert_eq;
+
+    fn chunk(step: usize, stream_type: StreamType, data: &str) -> OutputChunk {
+        OutputChunk {
+            step,
+            stream: Stream {
+                stream_type,
+                data: data.to_string(),
+            },
+        }
+    }
+
+    fn line(step: usize, stream_type: StreamType, text: &str) -> OutputLine {
+        OutputLine {
+            step,
+            stream_type,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_output_lines() {
+        let lines = output_lines(&[
+            chunk(0, StreamType::Stdout, "hello\nwor"),
+            chunk(2, StreamType::Stdout, "ld\n"),
+            chunk(2, StreamType::Stderr, "Warning: oops\n"),
+            chunk(3, StreamType::Stdout, "\nbye"),
+        ]);
+        assert_eq!(
+            vec![
+                line(0, StreamType::Stdout, "hello"),
+                line(0, StreamType::Stdout, "world"),
+                line(2, StreamType::Stderr, "Warning: oops"),
+                line(3, StreamType::Stdout, ""),
+                line(3, StreamType::Stdout, "bye"),
+            ],
+            lines
+        );
+    }
+}
@@ -1,20 +1,130 @@
-
 use crate::dbgp::client::Property;
 use crate::dbgp::client::PropertyType;
 use crate::theme::Scheme;
+use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// state shared by all levels while drawing a tree of properties
+#[derive(Default)]
+pub struct PropertyTree<'a> {
+    /// filter segments in reverse order
+    pub filter_path: Vec<&'a str>,
+    /// lines before this line are counted but not rendered
+    pub truncate_until: u32,
+    pub line_no: u32,
+    /// fullnames of the properties whose children are hidden
+    pub collapsed: Option<&'a HashSet<String>>,
+    /// the property of each line, closing braces and the "more" line belong
+    /// to their parent
+    pub rows: Vec<PropertyRow<'a>>,
+    /// changes since the previous step
+    pub changes: Option<&'a PropertyChanges>,
+}
+
+/// a line of the tree and the property it belongs to
+#[derive(Clone, Copy)]
+pub struct PropertyRow<'a> {
+    pub property: &'a Property,
+    pub level: usize,
+    /// false for closing braces and the "more" line
+    pub is_header: bool,
+}
+
+impl<'a> PropertyTree<'a> {
+    fn push<'l>(&mut self, lines: &mut Vec<Line<'l>>, line: Line<'l>) {
+        // only "render" lines that we need to
+        if self.line_no >= self.truncate_until {
+            lines.push(line);
+        }
+        self.line_no += 1;
+    }
+
+    fn push_row(&mut self, property: &'a Property, level: usize, is_header: bool) {
+        self.rows.push(PropertyRow {
+            property,
+            level,
+            is_header,
+        });
+    }
+
+    fn is_collapsed(&self, property: &Property) -> bool {
+        self.collapsed
+            .is_some_and(|collapsed| collapsed.contains(&property.fullname))
+    }
+
+    fn change(&self, property: &Property) -> Option<Change> {
+        self.changes.and_then(|changes| changes.get(&property.fullname))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Change {
+    Changed,
+    New,
+}
+
+/// differences between the properties of a frame and the properties of the
+/// same frame in the previous step, keyed by fullname
+#[derive(Debug, Default)]
+pub struct PropertyChanges {
+    changes: HashMap<String, Change>,
+    /// properties which are no longer defined
+    pub removed: Vec<Property>,
+}
 
-pub fn draw_properties(
+impl PropertyChanges {
+    pub fn diff(current: Vec<&Property>, previous: Vec<&Property>) -> Self {
+        let mut changes = Self::default();
+        changes.diff_level(current, previous);
+        changes
+    }
+
+    pub fn get(&self, fullname: &str) -> Option<Change> {
+        self.changes.get(fullname).copied()
+    }
+
+    fn diff_level(&mut self, current: Vec<&Property>, previous: Vec<&Property>) {
+        for property in current.iter() {
+            let before = match previous.iter().find(|p| p.fullname == property.fullname) {
+                Some(before) => before,
+                None => {
+                    self.changes.insert(property.fullname.clone(), Change::New);
+                    continue;
+                }
+            };
+            if before.property_type != property.property_type
+                || before.value != property.value
+                || before.numchildren != property.numchildren
+            {
+                self.changes.insert(property.fullname.clone(), Change::Changed);
+            }
+            // children can only be compared if they were fetched in both steps
+            if !property.children.is_empty() && !before.children.is_empty() {
+                self.diff_level(
+                    property.children.defined_properties(),
+                    before.children.defined_properties(),
+                );
+            }
+        }
+        for before in previous {
+            if !current.iter().any(|p| p.fullname == before.fullname) {
+                self.removed.push(before.clone());
+            }
+        }
+    }
+}
+
+pub fn draw_properties<'a>(
     theme: &Scheme,
-    properties: Vec<&Property>,
+    properties: Vec<&'a Property>,
     lines: &mut Vec<Line>,
     level: usize,
-    filter_path: &mut Vec<&str>,
-    truncate_until: &u32,
-    line_no: &mut u32,
+    tree: &mut PropertyTree<'a>,
 ) {
-    let filter = filter_path.pop();
+    let filter = tree.filter_path.pop();
 
     for property in properties {
         if let Some(filter) = filter {
@@ -22,9 +132,13 @@ pub fn draw_properties(
                 continue;
             }
         }
+        let change = tree.change(property);
         let mut spans = vec![
             Span::raw("  ".repeat(level)),
-            Span::styled(property.name.to_string(), theme.syntax_label),
+            Span::styled(property.name.to_string(), match change {
+                Some(Change::New) => theme.diff_new,
+                _ => theme.syntax_label,
+            }),
             Span::raw(" ".to_string()),
             Span::styled(
                 property.type_name(),
@@ -34,7 +148,11 @@ pub fn draw_properties(
                 },
             ),
             Span::raw(" = ".to_string()),
-            render_value(theme, property),
+            match change {
+                Some(Change::Changed) => render_value(theme, property).style(theme.diff_changed),
+                Some(Change::New) => render_value(theme, property).style(theme.diff_new),
+                None => render_value(theme, property),
+            },
         ];
 
         let delimiters = match property.property_type {
@@ -42,24 +160,94 @@ pub fn draw_properties(
             _ => ("{", "}"),
         };
 
-        if !property.children.is_empty() {
+        // children which have not been fetched are shown as collapsed
+        let expanded = !property.children.is_empty() && !tree.is_collapsed(property);
+        if expanded {
             spans.push(Span::raw(delimiters.0).style(theme.syntax_brace));
+        } else if property.has_children() {
+            spans.push(
+                Span::raw(format!("{}…{}", delimiters.0, delimiters.1)).style(theme.syntax_brace),
+            );
         }
 
-        // only "render" lines that we need to
-        if *line_no >= *truncate_until {
-            lines.push(Line::from(spans));
-        }
-        *line_no += 1;
+        tree.push_row(property, level, true);
+        tree.push(lines, Line::from(spans));
 
-        if !property.children.is_empty() {
-            draw_properties(theme, property.children.defined_properties(), lines, level + 1, filter_path, truncate_until,  line_no);
-            if *line_no >= *truncate_until {
-                lines.push(Line::from(vec![
-                    Span::raw(format!("{}{}", "  ".repeat(level), delimiters.1))
-                ]).style(theme.syntax_brace));
+        if expanded {
+            draw_properties(theme, property.children.defined_properties(), lines, level + 1, tree);
+            if property.remaining_children() > 0 {
+                tree.push_row(property, level, false);
+                tree.push(
+                    lines,
+                    Line::from(vec![Span::raw(format!(
+                        "{}… {} of {}",
+                        "  ".repeat(level + 1),
+                        property.children.properties.len(),
+                        property.numchildren.unwrap_or_default(),
+                    ))])
+                    .style(theme.syntax_brace),
+                );
             }
-            *line_no += 1;
+            tree.push_row(property, level, false);
+            tree.push(
+                lines,
+                Line::from(vec![
+                    Span::raw(format!("{}{}", "  ".repeat(level), delimiters.1))
+                ]).style(theme.syntax_brace),
+            );
+        }
+    }
+}
+
+/// list the properties which were removed since the previous step, these lines
+/// have no rows and must be drawn after all other properties
+pub fn draw_removed_properties(theme: &Scheme, lines: &mut Vec<Line>, tree: &mut PropertyTree) {
+    let removed = match tree.changes {
+        Some(changes) => &changes.removed,
+        None => return,
+    };
+    for property in removed {
+        let line = Line::from(vec![
+            Span::raw(format!("{} {} = ", property.fullname, property.type_name())),
+            Span::raw(render_value(theme, property).content),
+        ])
+        .style(theme.diff_removed.crossed_out());
+        tree.push(lines, line);
+    }
+}
+
+/// the header row of the property on the given row
+pub fn header_row(rows: &[PropertyRow], index: usize) -> Option<usize> {
+    let row = rows.get(index)?;
+    rows[..=index]
+        .iter()
+        .rposition(|r| r.is_header && std::ptr::eq(r.property, row.property))
+}
+
+/// the header row of the parent of the property on the given row
+pub fn parent_row(rows: &[PropertyRow], index: usize) -> Option<usize> {
+    let level = rows.get(index)?.level;
+    rows[..index]
+        .iter()
+        .rposition(|r| r.is_header && r.level < level)
+}
+
+/// the header row of the next (or previous) property with the same parent
+pub fn sibling_row(rows: &[PropertyRow], index: usize, forward: bool) -> Option<usize> {
+    let level = rows.get(index)?.level;
+    let is_sibling = |r: &PropertyRow| r.is_header && r.level == level;
+    // stop at the end of the parent
+    let in_parent = |r: &&PropertyRow| r.level >= level;
+    match forward {
+        true => rows[index + 1..]
+            .iter()
+            .take_while(in_parent)
+            .position(is_sibling)
+            .map(|offset| index + 1 + offset),
+        false => {
+            let start = header_row(rows, index)?;
+            let offset = rows[..start].iter().rev().take_while(in_parent).position(is_sibling)?;
+            Some(start - 1 - offset)
         }
     }
 }
@@ -96,9 +284,7 @@ mod test {
             vec![],
             &mut lines,
             0,
-            &mut Vec::new(),
-            &0,
-            &mut 0,
+            &mut PropertyTree::default(),
         );
         assert_eq!(0, lines.len());
         Ok(())
@@ -108,8 +294,10 @@ mod test {
     fn test_draw_properties_two_levels() -> Result<()> {
         let mut lines = vec![];
         let mut prop1 = Property::default();
-        let mut prop2 = Property::default();
-        prop2.name = "bar".to_string();
+        let prop2 = Property {
+            name: "bar".to_string(),
+            ..Default::default()
+        };
         prop1.children = Properties::from_properties(vec![
             prop2
         ]);
@@ -122,9 +310,7 @@ mod test {
             ],
             &mut lines,
             0,
-            &mut Vec::new(),
-            &0,
-            &mut 0,
+            &mut PropertyTree::default(),
         );
         assert_eq!(vec![
             "foo string = \"\"{",
@@ -140,20 +326,22 @@ mod test {
     fn test_filter_property_multiple_level() -> Result<()> {
         let mut lines = vec![];
         let mut prop1 = Property::default();
-        let mut prop2 = Property::default();
+        let prop2 = Property {
+            name: "bar".to_string(),
+            ..Default::default()
+        };
         let prop3 = Property::default();
 
-        prop2.name = "bar".to_string();
         prop1.children = Properties::from_properties(vec![
             prop2
         ]);
         prop1.name = "foo".to_string();
 
         // segments are reversed
-        let filter = &mut vec![
-            "bar",
-            "foo",
-        ];
+        let mut tree = PropertyTree {
+            filter_path: vec!["bar", "foo"],
+            ..Default::default()
+        };
 
         draw_properties(
             &Theme::SolarizedDark.scheme(),
@@ -163,9 +351,7 @@ mod test {
             ],
             &mut lines,
             0,
-            filter,
-            &0,
-            &mut 0,
+            &mut tree,
         );
 
         assert_eq!(vec![
@@ -178,4 +364,107 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_property_changes() -> Result<()> {
+        let property = |fullname: &str, value: &str| Property {
+            name: fullname.to_string(),
+            fullname: fullname.to_string(),
+            value: Some(value.to_string()),
+            ..Default::default()
+        };
+        let mut foo = property("$foo", "");
+        foo.children = Properties::from_properties(vec![
+            property("$foo[0]", "1"),
+            property("$foo[1]", "2"),
+        ]);
+        let mut previous_foo = property("$foo", "");
+        previous_foo.children = Properties::from_properties(vec![
+            property("$foo[0]", "1"),
+            property("$foo[1]", "1"),
+            property("$foo[2]", "1"),
+        ]);
+        let bar = property("$bar", "1");
+        let previous_bar = property("$bar", "2");
+        let baz = property("$baz", "1");
+        let previous_qux = property("$qux", "1");
+
+        let changes = PropertyChanges::diff(
+            vec![&foo, &bar, &baz],
+            vec![&previous_foo, &previous_bar, &previous_qux],
+        );
+
+        assert_eq!(None, changes.get("$foo"));
+        assert_eq!(None, changes.get("$foo[0]"));
+        assert_eq!(Some(Change::Changed), changes.get("$foo[1]"));
+        assert_eq!(Some(Change::Changed), changes.get("$bar"));
+        assert_eq!(Some(Change::New), changes.get("$baz"));
+        assert_eq!(
+            vec!["$foo[2]", "$qux"],
+            changes.removed.iter().map(|p| p.fullname.as_str()).collect::<Vec<&str>>()
+        );
+
+        let mut lines = vec![];
+        let mut tree = PropertyTree {
+            changes: Some(&changes),
+            ..Default::default()
+        };
+        draw_properties(&Theme::SolarizedDark.scheme(), vec![&bar], &mut lines, 0, &mut tree);
+        draw_removed_properties(&Theme::SolarizedDark.scheme(), &mut lines, &mut tree);
+        assert_eq!(
+            vec!["$bar string = \"1\"", "$foo[2] string = \"1\"", "$qux string = \"1\""],
+            lines.iter().map(|l| l.to_string()).collect::<Vec<String>>()
+        );
+        assert_eq!(1, tree.rows.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_navigate_rows() -> Result<()> {
+        let mut foo = Property {
+            name: "foo".to_string(),
+            fullname: "$foo".to_string(),
+            ..Default::default()
+        };
+        foo.children = Properties::from_properties(vec![
+            Property {
+                name: "a".to_string(),
+                fullname: "$foo[\"a\"]".to_string(),
+                ..Default::default()
+            },
+            Property {
+                name: "b".to_string(),
+                fullname: "$foo[\"b\"]".to_string(),
+                ..Default::default()
+            },
+        ]);
+        let bar = Property {
+            name: "bar".to_string(),
+            fullname: "$bar".to_string(),
+            ..Default::default()
+        };
+        let mut tree = PropertyTree::default();
+        draw_properties(
+            &Theme::SolarizedDark.scheme(),
+            vec![&foo, &bar],
+            &mut vec![],
+            0,
+            &mut tree,
+        );
+
+        // 0 foo {, 1 a, 2 b, 3 }, 4 bar
+        let rows = tree.rows;
+        assert_eq!(5, rows.len());
+        assert_eq!(Some(0), header_row(&rows, 3));
+        assert_eq!(Some(0), parent_row(&rows, 2));
+        assert_eq!(None, parent_row(&rows, 4));
+        assert_eq!(Some(2), sibling_row(&rows, 1, true));
+        assert_eq!(None, sibling_row(&rows, 2, true));
+        assert_eq!(Some(1), sibling_row(&rows, 2, false));
+        assert_eq!(Some(4), sibling_row(&rows, 0, true));
+        assert_eq!(Some(4), sibling_row(&rows, 3, true));
+        assert_eq!(Some(0), sibling_row(&rows, 4, false));
+        assert_eq!(None, sibling_row(&rows, 0, false));
+        Ok(())
+    }
 }
@@ -1,17 +1,29 @@
+use super::breakpoints::breakpoint_label;
+use super::breakpoints::BreakpointDialogState;
+use super::breakpoints::BreakpointsComponent;
 use super::context::ContextComponent;
+use super::context::PropertyDialogState;
 use super::eval::EvalComponent;
 use super::eval::EvalState;
+use super::output::OutputComponent;
 use super::source::SourceComponent;
 use super::stack::StackComponent;
+use super::step_until::StepUntilDialogState;
+use super::timeline::TimelineState;
+use super::watches::WatchDialogState;
+use super::watches::WatchesComponent;
 use super::Col;
 use super::ComponentType;
 use super::Pane;
 use super::View;
 use crate::app::App;
-use crate::app::ListenStatus;
+use crate::app::SelectedView;
+use crate::app::SourceContext;
+use crate::dbgp::client::BreakpointType;
 use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
 use crossterm::event::KeyCode;
-use crossterm::event::KeyModifiers;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Rect;
@@ -20,6 +32,7 @@ use ratatui::widgets::Borders;
 use ratatui::widgets::Clear;
 use ratatui::Frame;
 use std::cell::Cell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub struct SessionView {}
@@ -35,61 +48,62 @@ impl View for SessionView {
             return delegate_event_to_pane(app, event);
         }
 
-        let multiplier = if KeyModifiers::SHIFT == input_event.modifiers & KeyModifiers::SHIFT {
-            10
-        } else {
-            1
-        };
+        // numeric prefixes repeat the following motion and are not remappable
+        if let KeyCode::Char(char @ '0'..='9') = input_event.code {
+            return Some(AppEvent::PushInputPlurality(char));
+        }
 
         // handle global session events
-        match input_event.code {
-            KeyCode::Tab => return Some(AppEvent::NextPane),
-            KeyCode::BackTab => return Some(AppEvent::PreviousPane),
-            KeyCode::Enter => return Some(AppEvent::ToggleFullscreen),
-            KeyCode::Left => return Some(AppEvent::Scroll((0, -multiplier))),
-            KeyCode::Right => return Some(AppEvent::Scroll((0, multiplier))),
-            KeyCode::Up => return Some(AppEvent::Scroll((-multiplier, 0))),
-            KeyCode::Down => return Some(AppEvent::Scroll((multiplier, 0))),
-            KeyCode::Char(char) => match char {
-                'e' => return Some(AppEvent::EvalStart),
-                'j' => return Some(AppEvent::Scroll((1, 0))),
-                'k' => return Some(AppEvent::Scroll((-1, 0))),
-                'J' => return Some(AppEvent::Scroll((10, 0))),
-                'K' => return Some(AppEvent::Scroll((-10, 0))),
-                'l' => return Some(AppEvent::Scroll((0, 1))),
-                'L' => return Some(AppEvent::Scroll((0, 10))),
-                'h' => return Some(AppEvent::Scroll((0, -1))),
-                'H' => return Some(AppEvent::Scroll((0, -10))),
-                '0'..='9' => return Some(AppEvent::PushInputPlurality(char)),
-                _ => (),
-            },
-            _ => (),
-        };
+        if let Some(action) = app.keymap().action(Scope::Session, &input_event) {
+            let next_event = match action {
+                Action::PaneNext => Some(AppEvent::NextPane),
+                Action::PanePrevious => Some(AppEvent::PreviousPane),
+                Action::PaneFullscreen => Some(AppEvent::ToggleFullscreen),
+                Action::Eval => Some(AppEvent::EvalStart),
+                Action::BreakpointAddException => {
+                    Some(AppEvent::BreakpointAdd(BreakpointType::Exception))
+                }
+                Action::BreakpointAddCall => Some(AppEvent::BreakpointAdd(BreakpointType::Call)),
+                Action::BreakpointAddReturn => {
+                    Some(AppEvent::BreakpointAdd(BreakpointType::Return))
+                }
+                Action::ScrollDown => Some(AppEvent::Scroll((1, 0))),
+                Action::ScrollUp => Some(AppEvent::Scroll((-1, 0))),
+                Action::ScrollDown10 => Some(AppEvent::Scroll((10, 0))),
+                Action::ScrollUp10 => Some(AppEvent::Scroll((-10, 0))),
+                Action::ScrollRight => Some(AppEvent::Scroll((0, 1))),
+                Action::ScrollRight10 => Some(AppEvent::Scroll((0, 10))),
+                Action::ScrollLeft => Some(AppEvent::Scroll((0, -1))),
+                Action::ScrollLeft10 => Some(AppEvent::Scroll((0, -10))),
+                Action::Disconnect => app.session().map(|s| AppEvent::Disconnect(s.id)),
+                Action::Interrupt => Some(AppEvent::Interrupt),
+                Action::Stop => Some(AppEvent::Stop),
+                Action::Detach => Some(AppEvent::Detach),
+                _ => None,
+            };
+            if next_event.is_some() {
+                return next_event;
+            }
+        }
 
         let next_event: Option<AppEvent> = match app.session_view.mode {
-            SessionViewMode::Current => match input_event.code {
-                KeyCode::Char(char) => match char {
-                    '+' => Some(AppEvent::ContextDepth(1)),
-                    '-' => Some(AppEvent::ContextDepth(-1)),
-                    'r' => Some(AppEvent::Run),
-                    'n' => Some(AppEvent::StepInto),
-                    'N' => Some(AppEvent::StepOver),
-                    'o' => Some(AppEvent::StepOut),
-                    'p' => Some(AppEvent::ChangeSessionViewMode(SessionViewMode::History)),
-                    'd' => Some(AppEvent::Disconnect),
-                    _ => None,
-                },
+            SessionViewMode::Current => match app.keymap().action(Scope::Current, &input_event) {
+                Some(Action::ContextDepthIncrease) => Some(AppEvent::ContextDepth(1)),
+                Some(Action::ContextDepthDecrease) => Some(AppEvent::ContextDepth(-1)),
+                Some(Action::Run) => Some(AppEvent::Run),
+                Some(Action::StepInto) => Some(AppEvent::StepInto),
+                Some(Action::StepOver) => Some(AppEvent::StepOver),
+                Some(Action::StepOut) => Some(AppEvent::StepOut),
+                Some(Action::StepUntil) => Some(AppEvent::StepUntilStart),
+                Some(Action::HistoryEnter) => {
+                    Some(AppEvent::ChangeSessionViewMode(SessionViewMode::History))
+                }
                 _ => None,
             },
-            SessionViewMode::History => match input_event.code {
-                KeyCode::Esc => escape(app),
-                KeyCode::Char(c) => match c {
-                    'n' => Some(AppEvent::HistoryNext),
-                    'p' => Some(AppEvent::HistoryPrevious),
-                    'd' => Some(AppEvent::Disconnect),
-                    'b' => escape(app),
-                    _ => None,
-                },
+            SessionViewMode::History => match app.keymap().action(Scope::History, &input_event) {
+                Some(Action::HistoryNext) => Some(AppEvent::HistoryNext),
+                Some(Action::HistoryPrevious) => Some(AppEvent::HistoryPrevious),
+                Some(Action::HistoryExit) => escape(app),
                 _ => None,
             },
         };
@@ -138,8 +152,12 @@ impl View for SessionView {
 }
 
 fn escape(app: &App) -> Option<AppEvent> {
-    if app.listening_status == ListenStatus::Refusing {
-        Some(AppEvent::Listen)
+    if !app.session().is_some_and(|s| s.is_connected()) {
+        // return to the other sessions or wait for the next connection
+        match app.sessions.len() > 1 {
+            true => Some(AppEvent::ChangeView(SelectedView::Sessions)),
+            false => Some(AppEvent::Listen),
+        }
     } else {
         Some(AppEvent::ChangeSessionViewMode(SessionViewMode::Current))
     }
@@ -163,6 +181,9 @@ fn delegate_event_to_pane(app: &mut App, event: AppEvent) -> Option<AppEvent> {
         ComponentType::Context => ContextComponent::handle(app, event),
         ComponentType::Stack => StackComponent::handle(app, event),
         ComponentType::Eval => EvalComponent::handle(app, event),
+        ComponentType::Breakpoints => BreakpointsComponent::handle(app, event),
+        ComponentType::Output => OutputComponent::handle(app, event),
+        ComponentType::Watches => WatchesComponent::handle(app, event),
     }
 }
 
@@ -170,41 +191,87 @@ fn build_pane_widget(frame: &mut Frame, app: &App, pane: &Pane, area: Rect, inde
     let block = Block::default()
         .borders(Borders::all())
         .title_bottom(match pane.component_type {
-            ComponentType::Source => match app.history.current() {
-                Some(c) => c
-                    .source(app.session_view.stack_depth())
-                    .filename
-                    .to_string(),
+            ComponentType::Source => match app.source() {
+                Some(source) => {
+                    // indicate if the source was read from the local checkout
+                    let document = app.session().and_then(|s| s.workspace.document(&source.filename));
+                    let filename = match document {
+                        Some(document) => match &document.local_path {
+                            Some(path) => format!("{} [local]", path.display()),
+                            None => format!("{} [remote]", source.filename),
+                        },
+                        None => source.filename.clone(),
+                    };
+                    match app.breakpoints.at_line(
+                        &app.path_mappings.local_uri(&source.filename),
+                        app.session_view.source_cursor,
+                    ) {
+                        Some(breakpoint) => {
+                            format!("{} (breakpoint {})", filename, breakpoint_label(breakpoint))
+                        }
+                        None => filename,
+                    }
+                }
                 None => "".to_string(),
             },
             ComponentType::Context => format!(
                 "Context(fetch-depth: {}, filter: {})",
                 app.context_depth,
                 match app.session_view.context_filter.input.value() {
-                    "" => "press 'f' to filter with dot notation",
-                    _ => app.session_view.context_filter.input.value(),
+                    "" => format!(
+                        "press '{}' to filter with dot notation",
+                        app.keymap().keys(Action::ContextFilter)
+                    ),
+                    filter => filter.to_string(),
                 }
             ),
             ComponentType::Stack => format!(
                 "Stack({}/{}, fetch-depth: {})",
                 app.session_view.stack_depth(),
-                match app.history.current() {
+                match app.history_entry() {
                     Some(e) => e.stacks.len().saturating_sub(1),
                     None => 0,
                 },
                 app.stack_max_context_fetch,
             ),
-            ComponentType::Eval => match app.history.current() {
+            ComponentType::Eval => match app.history_entry() {
                 Some(entry) => format!(
                     "Eval: {}",
                     if let Some(eval) = &entry.eval {
                         eval.expr.clone()
                     } else {
-                        "Press 'e' to enter an expression".to_string()
+                        format!("Press '{}' to enter an expression", app.keymap().keys(Action::Eval))
                     }
                 ),
                 None => "".to_string(),
             },
+            ComponentType::Breakpoints => format!(
+                "Breakpoints({}) [{}] enable/disable [{}] delete [{}] go to",
+                app.breakpoints.breakpoints.len(),
+                app.keymap().keys(Action::BreakpointToggleState),
+                app.keymap().keys(Action::BreakpointDelete),
+                app.keymap().keys(Action::BreakpointJump),
+            ),
+            ComponentType::Output => match app.session() {
+                Some(session) => format!(
+                    "Output({} chunks, {} from step {})",
+                    session.output.len(),
+                    session
+                        .output
+                        .iter()
+                        .filter(|c| c.step == session.history.offset)
+                        .count(),
+                    session.history.offset + 1
+                ),
+                None => "Output".to_string(),
+            },
+            ComponentType::Watches => format!(
+                "Watches({}) [{}] add [{}] edit [{}] delete",
+                app.watches.len(),
+                app.keymap().keys(Action::WatchAdd),
+                app.keymap().keys(Action::WatchEdit),
+                app.keymap().keys(Action::WatchDelete),
+            ),
         })
         .style(match index == app.session_view.current_pane {
             true => app.theme().pane_border_active,
@@ -227,6 +294,15 @@ fn build_pane_widget(frame: &mut Frame, app: &App, pane: &Pane, area: Rect, inde
         ComponentType::Eval => {
             EvalComponent::draw(app, frame, block.inner(area));
         }
+        ComponentType::Breakpoints => {
+            BreakpointsComponent::draw(app, frame, block.inner(area));
+        }
+        ComponentType::Output => {
+            OutputComponent::draw(app, frame, block.inner(area));
+        }
+        ComponentType::Watches => {
+            WatchesComponent::draw(app, frame, block.inner(area));
+        }
     };
 }
 
@@ -247,11 +323,26 @@ impl SearchState {
 pub struct SessionViewState {
     pub full_screen: bool,
     pub source_scroll: (u16, u16),
+    pub source_cursor: u32,
+    /// show a document other than the one of the current stack frame
+    pub source_override: Option<SourceContext>,
     pub source_area: Cell<Rect>,
     pub eval_state: EvalState,
+    pub breakpoint_dialog: BreakpointDialogState,
+    pub property_dialog: PropertyDialogState,
+    pub watch_dialog: WatchDialogState,
+    pub step_until_dialog: StepUntilDialogState,
     pub context_scroll: (u16, u16),
     pub context_filter: SearchState,
+    /// fullnames of the properties collapsed in the context pane
+    pub context_collapsed: HashSet<String>,
+    /// the context shown in the context pane, e.g. locals or superglobals
+    pub context_id: u32,
     pub stack_scroll: (u16, u16),
+    pub breakpoints_scroll: (u16, u16),
+    pub output_scroll: (u16, u16),
+    pub watches_scroll: (u16, u16),
+    pub timeline: TimelineState,
     pub mode: SessionViewMode,
     pub panes: Vec<Pane>,
     pub current_pane: usize,
@@ -262,15 +353,27 @@ impl SessionViewState {
         Self {
             full_screen: false,
             source_scroll: (0, 0),
+            source_cursor: 0,
+            source_override: None,
             source_area: Cell::new(Rect::new(0, 0, 0, 0)),
             context_scroll: (0, 0),
+            context_collapsed: HashSet::new(),
+            context_id: 0,
             eval_state: EvalState::default(),
+            breakpoint_dialog: BreakpointDialogState::default(),
+            property_dialog: PropertyDialogState::default(),
+            watch_dialog: WatchDialogState::default(),
+            step_until_dialog: StepUntilDialogState::default(),
             context_filter: SearchState {
                 show: false,
                 search: String::new(),
                 input: tui_input::Input::default(),
             },
             stack_scroll: (0, 0),
+            breakpoints_scroll: (0, 0),
+            output_scroll: (0, 0),
+            watches_scroll: (0, 0),
+            timeline: TimelineState::default(),
             current_pane: 0,
             mode: SessionViewMode::Current,
             panes: vec![
@@ -284,9 +387,19 @@ impl SessionViewState {
                     constraint: ratatui::layout::Constraint::Fill(1),
                     col: Col::Left,
                 },
+                Pane {
+                    component_type: ComponentType::Watches,
+                    constraint: ratatui::layout::Constraint::Fill(1),
+                    col: Col::Left,
+                },
+                Pane {
+                    component_type: ComponentType::Output,
+                    constraint: ratatui::layout::Constraint::Fill(1),
+                    col: Col::Left,
+                },
                 Pane {
                     component_type: ComponentType::Context,
-                    constraint: ratatui::layout::Constraint::Percentage(75),
+                    constraint: ratatui::layout::Constraint::Percentage(60),
                     col: Col::Right,
                 },
                 Pane {
@@ -294,6 +407,11 @@ impl SessionViewState {
                     constraint: ratatui::layout::Constraint::Fill(1),
                     col: Col::Right,
                 },
+                Pane {
+                    component_type: ComponentType::Breakpoints,
+                    constraint: ratatui::layout::Constraint::Fill(1),
+                    col: Col::Right,
+                },
             ],
         }
     }
@@ -324,6 +442,7 @@ impl SessionViewState {
         self.context_scroll = (0, 0);
         self.stack_scroll = (0, 0);
         self.source_scroll = (0, 0);
+        self.output_scroll = (0, 0);
     }
 
     pub(crate) fn stack_depth(&self) -> u16 {
@@ -335,6 +454,22 @@ impl SessionViewState {
         let mid_point = (area.height as u32).div_ceil(2);
         let offset = line_no.saturating_sub(mid_point);
         self.source_scroll.0 = offset as u16;
+        self.source_cursor = line_no;
+    }
+
+    // move the source cursor and scroll the source view if the cursor
+    // would otherwise leave the visible area
+    pub(crate) fn move_source_cursor(&mut self, amount: i16, line_count: u32) {
+        let cursor = (self.source_cursor as i64 + amount as i64).clamp(1, line_count.max(1) as i64);
+        self.source_cursor = cursor as u32;
+
+        let height = self.source_area.get().height as u32;
+        let top = self.source_scroll.0 as u32;
+        if self.source_cursor <= top {
+            self.source_scroll.0 = self.source_cursor.saturating_sub(1) as u16;
+        } else if self.source_cursor > top + height {
+            self.source_scroll.0 = (self.source_cursor - height) as u16;
+        }
     }
 
     pub(crate) fn stack_level(&self) -> usize {
@@ -355,8 +490,8 @@ mod test {
 
     #[test]
     pub fn panes() {
-        let mut view = SessionViewState::default();
-        view.panes = vec![
+        let view = SessionViewState {
+            panes: vec![
             Pane {
                 component_type: ComponentType::Stack,
                 constraint: Constraint::Min(1),
@@ -372,20 +507,24 @@ mod test {
                 constraint: Constraint::Min(1),
                 col: Col::Right,
             },
-        ];
+            ],
+            ..Default::default()
+        };
         assert_eq!(1, view.panes(Col::Left).len());
         assert_eq!(2, view.panes(Col::Right).len());
     }
 
     #[test]
     pub fn scroll_to_line() {
-        let mut view = SessionViewState::default();
-        view.source_area = Cell::new(Rect {
-            x: 0,
-            y: 0,
-            width: 0,
-            height: 10,
-        });
+        let mut view = SessionViewState {
+            source_area: Cell::new(Rect {
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 10,
+            }),
+            ..Default::default()
+        };
         view.scroll_to_line(0);
 
         assert_eq!(0, view.source_scroll.0);
@@ -405,4 +544,36 @@ mod test {
         view.scroll_to_line(100);
         assert_eq!(95, view.source_scroll.0);
     }
+
+    #[test]
+    pub fn move_source_cursor() {
+        let mut view = SessionViewState {
+            source_area: Cell::new(Rect {
+                x: 0,
+                y: 0,
+                width: 0,
+                height: 10,
+            }),
+            ..Default::default()
+        };
+        view.move_source_cursor(1, 100);
+        assert_eq!(1, view.source_cursor);
+        assert_eq!(0, view.source_scroll.0);
+
+        view.move_source_cursor(9, 100);
+        assert_eq!(10, view.source_cursor);
+        assert_eq!(0, view.source_scroll.0);
+
+        view.move_source_cursor(1, 100);
+        assert_eq!(11, view.source_cursor);
+        assert_eq!(1, view.source_scroll.0);
+
+        view.move_source_cursor(-10, 100);
+        assert_eq!(1, view.source_cursor);
+        assert_eq!(0, view.source_scroll.0);
+
+        view.move_source_cursor(200, 100);
+        assert_eq!(100, view.source_cursor);
+        assert_eq!(90, view.source_scroll.0);
+    }
 }
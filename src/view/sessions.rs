@@ -0,0 +1,95 @@
+use super::View;
+use crate::app::App;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+pub struct SessionsView {}
+
+impl View for SessionsView {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        let input_event = match event {
+            AppEvent::Input(e) => e,
+            _ => return None,
+        };
+        if let Some(action) = app.keymap().action(Scope::Sessions, &input_event) {
+            return match action {
+                Action::SessionSwitch => Some(AppEvent::SwitchSession),
+                Action::SessionClose => Some(AppEvent::CloseSession),
+                _ => None,
+            };
+        }
+        if let Some(Action::DialogCancel) = app.keymap().action(Scope::Dialog, &input_event) {
+            return match app.session() {
+                Some(_) => Some(AppEvent::ChangeView(crate::app::SelectedView::Session)),
+                None => Some(AppEvent::Listen),
+            };
+        }
+        match app.keymap().action(Scope::Session, &input_event) {
+            Some(Action::ScrollDown) => Some(AppEvent::ScrollSessions((1, 0))),
+            Some(Action::ScrollUp) => Some(AppEvent::ScrollSessions((-1, 0))),
+            Some(Action::ScrollDown10) => Some(AppEvent::ScrollSessions((10, 0))),
+            Some(Action::ScrollUp10) => Some(AppEvent::ScrollSessions((-10, 0))),
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let selected = app.sessions_scroll.0 as usize;
+        let mut lines: Vec<Line> = Vec::new();
+
+        for (index, session) in app.sessions.iter().enumerate() {
+            lines.push(
+                Line::from(vec![
+                    Span::raw(match app.current_session == Some(index) {
+                        true => "▶ ",
+                        false => "  ",
+                    }),
+                    Span::styled(format!("{:<16}", session.label()), app.theme().syntax_label),
+                    Span::styled(
+                        format!(
+                            "{:<14}",
+                            match session.is_connected() {
+                                true => "connected",
+                                false => "disconnected",
+                            }
+                        ),
+                        match session.is_connected() {
+                            true => app.theme().widget_active,
+                            false => app.theme().widget_inactive,
+                        },
+                    ),
+                    Span::raw(format!("{:<10}", format!("{} steps", session.history.len()))),
+                    Span::raw(session.fileuri.clone()),
+                ])
+                .style(match index == selected {
+                    true => app.theme().source_line_highlight,
+                    false => app.theme().source_line,
+                }),
+            );
+        }
+
+        let y_scroll = (selected as u16 + 3).saturating_sub(area.height);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((y_scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::all())
+                    .title(format!(
+                        "Sessions ({}) [{}] switch [{}] close",
+                        app.sessions.len(),
+                        app.keymap().keys(Action::SessionSwitch),
+                        app.keymap().keys(Action::SessionClose),
+                    ))
+                    .style(app.theme().pane_border_active),
+            ),
+            area,
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use super::properties::Change;
 use super::View;
 use crate::app::App;
+use crate::dbgp::client::BreakpointState;
 use crate::dbgp::client::Property;
 use crate::dbgp::client::PropertyType;
 use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Position;
@@ -15,15 +19,25 @@ use ratatui::Frame;
 pub struct SourceComponent {}
 
 impl View for SourceComponent {
-    fn handle(_: &mut App, event: AppEvent) -> Option<AppEvent> {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
         match event {
             AppEvent::Scroll(amount) => Some(AppEvent::ScrollSource(amount)),
+            AppEvent::Input(e) => match app.keymap().action(Scope::Source, &e) {
+                Some(Action::BreakpointToggle) => Some(AppEvent::ToggleBreakpoint),
+                Some(Action::BreakpointEdit) => Some(AppEvent::BreakpointEdit),
+                Some(Action::RunToCursor) => Some(AppEvent::RunToCursor),
+                _ => None,
+            },
             _ => None,
         }
     }
 
     fn draw(app: &App, frame: &mut Frame, area: Rect) {
-        let history_entry = match app.history.current() {
+        let session = match app.session() {
+            Some(session) => session,
+            None => return,
+        };
+        let history_entry = match session.history.current() {
             Some(s) => s,
             None => return,
         };
@@ -46,20 +60,47 @@ impl View for SourceComponent {
             None => return,
             Some(stack) => stack
         };
+        let source = match &app.session_view.source_override {
+            Some(source) => source,
+            None => &stack.source,
+        };
+
+        // highlight variables which changed since the previous step
+        let changes = session.history.changes(app.session_view.stack_depth(), 0);
+
+        let breakpoint_filename = app.path_mappings.local_uri(&source.filename);
 
         // trunacte the hidden lines
         let truncate_until = app.session_view.source_scroll.0 as u32 + 1;
         let truncate_from = app.session_view.source_scroll.0 as u32 + (rows[0].height as u32) + 1;
 
-        for (line_offset, line) in stack.source.source.lines().enumerate() {
+        for (line_offset, line) in source.source.lines().enumerate() {
             let line_no = line_offset + 1;
             if (line_no as u32) < truncate_until || (line_no as u32) > truncate_from {
                 continue
             }
-            let is_current_line = stack.source.line_no == line_no as u32;
+            let is_current_line = source.line_no == line_no as u32;
+            let is_cursor_line = app.session_view.source_cursor == line_no as u32;
 
             lines.push(Line::from(vec![
-                Span::styled(format!("{:<6}", line_no), app.theme().source_line_no),
+                match app.breakpoints.at_line(&breakpoint_filename, line_no as u32) {
+                    Some(breakpoint) => Span::styled(
+                        match (
+                            &breakpoint.state,
+                            breakpoint.expression.is_some() || breakpoint.hit_condition.is_some(),
+                        ) {
+                            (BreakpointState::Disabled, _) => "○",
+                            (_, true) => "◆",
+                            (_, false) => "●",
+                        },
+                        app.theme().source_breakpoint,
+                    ),
+                    None => Span::raw(" "),
+                },
+                Span::styled(format!("{:<6}", line_no), match is_cursor_line {
+                    true => app.theme().source_cursor,
+                    false => app.theme().source_line_no,
+                }),
                 match is_current_line {
                     // highlight the current line
                     true => Span::styled(line.to_string(), app.theme().source_line_highlight),
@@ -70,18 +111,23 @@ impl View for SourceComponent {
             {
                 // record annotations to add at the end of the line
                 let mut labels = vec![Span::raw("// ")];
-                for var in app.document_variables.get(&stack.source.filename, line_no as u32).iter() {
-                    match render_label(&var.value) {
-                        Some(label) => labels.push(Span::raw(label)),
+                for var in session.document_variables.get(&source.filename, line_no as u32).iter() {
+                    let label = match render_label(&var.value) {
+                        Some(label) => label,
                         None => continue,
                     };
+                    labels.push(match changes.as_ref().and_then(|c| c.get(&var.value.fullname)) {
+                        Some(Change::Changed) => Span::styled(label, app.theme().diff_changed),
+                        Some(Change::New) => Span::styled(label, app.theme().diff_new),
+                        None => Span::raw(label),
+                    });
                     labels.push(Span::raw(","));
                 }
                 if labels.len() > 1 {
                     labels.pop();
                     annotations.push((
                         line_offset,
-                        line.len() + 8,
+                        line.len() + 9,
                         Line::from(labels).style(
                             match is_current_line {
                                 true =>  app.theme().source_annotation,
@@ -207,6 +253,7 @@ mod test {
             classname: None,
             page: None,
             pagesize: None,
+            numchildren: None,
             property_type,
             facet: None,
             size: None,
@@ -225,6 +272,7 @@ mod test {
             classname: None,
             page: None,
             pagesize: None,
+            numchildren: None,
             property_type: PropertyType::Resource,
             facet: Some("private".to_string()),
             size: None,
@@ -243,6 +291,7 @@ mod test {
             classname: Some("Foo".to_string()),
             page: Some(0),
             pagesize: Some(32),
+            numchildren: None,
             property_type: PropertyType::Object,
             facet: None,
             size: None,
@@ -253,6 +302,7 @@ mod test {
                     classname: None,
                     page: None,
                     pagesize: None,
+                    numchildren: None,
                     property_type: PropertyType::Bool,
                     facet: Some("public".to_string()),
                     size: None,
@@ -268,6 +318,7 @@ mod test {
                     classname: None,
                     page: None,
                     pagesize: None,
+                    numchildren: None,
                     property_type: PropertyType::String,
                     facet: Some("public".to_string()),
                     size: Some(3),
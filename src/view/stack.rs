@@ -18,7 +18,7 @@ impl View for StackComponent {
     }
 
     fn draw(app: &App, frame: &mut Frame, area: Rect) {
-        let entry = match app.history.current() {
+        let entry = match app.history_entry() {
             Some(s) => s,
             None => return,
         };
@@ -34,6 +34,10 @@ impl View for StackComponent {
                     .to_string(),
             ).style(match stack.level == app.session_view.stack_depth() {
                 true => app.theme().source_line_highlight,
+                // frames which are skipped when stepping
+                false if app.config.step_filters.is_filtered_remote(&stack.source.filename, &app.path_mappings) => {
+                    app.theme().source_annotation_historic
+                }
                 false => app.theme().source_line,
             }));
         }
@@ -0,0 +1,188 @@
+use super::centered_dialog;
+use super::View;
+use crate::app::App;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use anyhow::Result;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use tui_input::backend::crossterm::EventHandler;
+use tui_input::Input;
+
+pub struct StepUntilDialog {}
+
+const LABELS: [&str; 3] = ["Until", "Step", "Max steps"];
+const DEFAULT_MAX_STEPS: usize = 1000;
+
+pub struct StepUntilDialogState {
+    pub expression: Input,
+    /// "into" or "over"
+    pub step: Input,
+    pub max_steps: Input,
+    pub focus: usize,
+}
+
+impl Default for StepUntilDialogState {
+    fn default() -> Self {
+        Self {
+            expression: Input::default(),
+            step: Input::new("over".to_string()),
+            max_steps: Input::new(DEFAULT_MAX_STEPS.to_string()),
+            focus: 0,
+        }
+    }
+}
+
+/// what to step until an expression is true
+#[derive(Debug, PartialEq)]
+pub struct StepUntil {
+    pub expression: String,
+    pub step_into: bool,
+    pub max_steps: usize,
+}
+
+impl StepUntilDialogState {
+    fn inputs(&self) -> [&Input; 3] {
+        [&self.expression, &self.step, &self.max_steps]
+    }
+
+    fn focused_input_mut(&mut self) -> &mut Input {
+        match self.focus {
+            0 => &mut self.expression,
+            1 => &mut self.step,
+            _ => &mut self.max_steps,
+        }
+    }
+
+    pub fn step_until(&self) -> Result<StepUntil> {
+        let expression = self.expression.value().trim();
+        if expression.is_empty() {
+            anyhow::bail!("Expression must not be empty");
+        }
+        let step_into = match self.step.value().trim() {
+            "into" => true,
+            "over" => false,
+            step => anyhow::bail!("Step must be \"into\" or \"over\", got \"{}\"", step),
+        };
+        let max_steps = match self.max_steps.value().trim().parse::<usize>() {
+            Ok(max_steps) if max_steps > 0 => max_steps,
+            _ => anyhow::bail!("Max steps must be a number greater than 0"),
+        };
+        Ok(StepUntil {
+            expression: expression.to_string(),
+            step_into,
+            max_steps,
+        })
+    }
+}
+
+impl View for StepUntilDialog {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        match event {
+            AppEvent::Input(e) => {
+                let action = app.keymap().action(Scope::Dialog, &e);
+                let state = &mut app.session_view.step_until_dialog;
+                match action {
+                    Some(Action::DialogCancel) => return Some(AppEvent::StepUntilDialogCancel),
+                    Some(Action::DialogApply) => return Some(AppEvent::StepUntilDialogApply),
+                    Some(Action::DialogNextField) => state.focus = (state.focus + 1) % LABELS.len(),
+                    Some(Action::DialogPreviousField) => {
+                        state.focus = (state.focus + LABELS.len() - 1) % LABELS.len()
+                    }
+                    _ => {
+                        state
+                            .focused_input_mut()
+                            .handle_event(&crossterm::event::Event::Key(e));
+                    }
+                };
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let state = &app.session_view.step_until_dialog;
+        let label_width = LABELS.iter().map(|l| l.len()).max().unwrap_or(0) + 2;
+
+        let darea = centered_dialog(LABELS.len() as u16 + 2, area);
+        frame.render_widget(Clear, darea);
+
+        let lines: Vec<Line> = LABELS
+            .iter()
+            .zip(state.inputs())
+            .map(|(label, input)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}", format!("{}:", label), width = label_width),
+                        app.theme().syntax_label,
+                    ),
+                    Span::raw(input.value()).style(app.theme().text_input),
+                ])
+            })
+            .collect();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::all())
+                    .title(format!(
+                        "Step until the expression is true [{}] next field",
+                        app.keymap().keys(Action::DialogNextField)
+                    ))
+                    .title_bottom("Step: into or over")
+                    .style(app.theme().pane_border_active),
+            ),
+            darea,
+        );
+
+        let input = state.inputs()[state.focus];
+        let width = darea.width.saturating_sub(label_width as u16 + 2).max(3);
+        let scroll = input.visual_scroll(width as usize);
+        let x = input.visual_cursor().max(scroll) - scroll + 1 + label_width;
+        frame.set_cursor_position((darea.x + x as u16, darea.y + 1 + state.focus as u16));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_step_until() {
+        let mut state = StepUntilDialogState {
+            expression: Input::new(" $i > 100 ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            StepUntil {
+                expression: "$i > 100".to_string(),
+                step_into: false,
+                max_steps: 1000,
+            },
+            state.step_until().unwrap()
+        );
+
+        state.step = Input::new("into".to_string());
+        state.max_steps = Input::new("50".to_string());
+        assert!(state.step_until().unwrap().step_into);
+        assert_eq!(50, state.step_until().unwrap().max_steps);
+
+        state.step = Input::new("out".to_string());
+        assert!(state.step_until().is_err());
+        state.step = Input::new("over".to_string());
+        state.max_steps = Input::new("0".to_string());
+        assert!(state.step_until().is_err());
+        state.max_steps = Input::new("10".to_string());
+        state.expression = Input::default();
+        assert!(state.step_until().is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use super::properties::render_value;
+use super::View;
+use crate::app::App;
+use crate::app::History;
+use crate::app::HistoryEntry;
+use crate::dbgp::client::Property;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+/// the value of a single property at every step of the history
+pub struct TimelineView {}
+
+#[derive(Default)]
+pub struct TimelineState {
+    pub fullname: String,
+    pub context_id: u32,
+    /// position of the frame counted from the bottom of the stack, frames
+    /// are matched from the bottom as calls and returns only change the top
+    pub from_bottom: usize,
+    pub filename: String,
+    pub function: Option<String>,
+    pub scroll: (u16, u16),
+}
+
+impl TimelineState {
+    /// follow the property of the frame at the given depth of the step
+    pub fn new(fullname: String, context_id: u32, entry: &HistoryEntry, stack_depth: u16) -> Option<Self> {
+        let frame = entry.stack(stack_depth)?;
+        Some(Self {
+            fullname,
+            context_id,
+            from_bottom: entry.stacks.len() - stack_depth as usize,
+            filename: frame.source.filename.clone(),
+            function: frame.function.clone(),
+            scroll: (0, 0),
+        })
+    }
+}
+
+/// a step in which the property is in scope
+pub struct TimelineRow<'a> {
+    /// index of the history entry
+    pub step: usize,
+    pub stack_depth: u16,
+    pub property: &'a Property,
+    pub filename: &'a str,
+    pub line_no: u32,
+}
+
+/// the steps where the property is in scope of the followed frame
+pub fn timeline<'a>(history: &'a History, state: &TimelineState) -> Vec<TimelineRow<'a>> {
+    history
+        .entries
+        .iter()
+        .enumerate()
+        .filter_map(|(step, entry)| {
+            let stack_depth = entry.stacks.len().checked_sub(state.from_bottom)?;
+            let frame = entry.stacks.get(stack_depth)?;
+            if frame.source.filename != state.filename || frame.function != state.function {
+                return None;
+            }
+            let property = frame.context(state.context_id)?.properties.find(&state.fullname)?;
+            Some(TimelineRow {
+                step,
+                stack_depth: stack_depth as u16,
+                property,
+                filename: &frame.source.filename,
+                line_no: frame.source.line_no,
+            })
+        })
+        .collect()
+}
+
+impl View for TimelineView {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        let input_event = match event {
+            AppEvent::Input(e) => e,
+            _ => return None,
+        };
+        match app.keymap().action(Scope::Dialog, &input_event) {
+            Some(Action::DialogApply) => return Some(AppEvent::TimelineJump),
+            Some(Action::DialogCancel) => {
+                return Some(AppEvent::ChangeView(crate::app::SelectedView::Session))
+            }
+            _ => (),
+        }
+        match app.keymap().action(Scope::Session, &input_event) {
+            Some(Action::ScrollDown) => Some(AppEvent::ScrollTimeline((1, 0))),
+            Some(Action::ScrollUp) => Some(AppEvent::ScrollTimeline((-1, 0))),
+            Some(Action::ScrollDown10) => Some(AppEvent::ScrollTimeline((10, 0))),
+            Some(Action::ScrollUp10) => Some(AppEvent::ScrollTimeline((-10, 0))),
+            Some(Action::ScrollRight) => Some(AppEvent::ScrollTimeline((0, 1))),
+            Some(Action::ScrollRight10) => Some(AppEvent::ScrollTimeline((0, 10))),
+            Some(Action::ScrollLeft) => Some(AppEvent::ScrollTimeline((0, -1))),
+            Some(Action::ScrollLeft10) => Some(AppEvent::ScrollTimeline((0, -10))),
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let session = match app.session() {
+            Some(session) => session,
+            None => return,
+        };
+        let state = &app.session_view.timeline;
+        let rows = timeline(&session.history, state);
+        let selected = state.scroll.0 as usize;
+        let mut lines: Vec<Line> = Vec::new();
+        let mut previous: Option<&Property> = None;
+
+        for (index, row) in rows.iter().enumerate() {
+            let value = render_value(&app.theme(), row.property);
+            // make the steps where the value changed stand out
+            let changed = previous.is_some_and(|p| {
+                p.value != row.property.value || p.property_type != row.property.property_type
+            });
+            lines.push(
+                Line::from(vec![
+                    Span::raw(match session.history.offset == row.step {
+                        true => "▶ ",
+                        false => "  ",
+                    }),
+                    Span::styled(format!("{:<8}", format!("#{}", row.step + 1)), app.theme().syntax_label),
+                    Span::styled(format!("{} ", row.property.type_name()), app.theme().syntax_type),
+                    match changed {
+                        true => value.style(app.theme().diff_changed),
+                        false => value,
+                    },
+                    Span::styled(
+                        format!("  {}:{}", row.filename, row.line_no),
+                        app.theme().source_annotation_historic,
+                    ),
+                ])
+                .style(match index == selected {
+                    true => app.theme().source_line_highlight,
+                    false => app.theme().source_line,
+                }),
+            );
+            previous = Some(row.property);
+        }
+
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "not in scope in any step",
+                app.theme().source_annotation_historic,
+            )));
+        }
+
+        let y_scroll = (selected as u16 + 3).saturating_sub(area.height);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((y_scroll, state.scroll.1)).block(
+                Block::default()
+                    .borders(Borders::all())
+                    .title(format!(
+                        "Timeline of {} ({} steps) [{}] go to step [{}] back",
+                        state.fullname,
+                        rows.len(),
+                        app.keymap().keys(Action::DialogApply),
+                        app.keymap().keys(Action::DialogCancel),
+                    ))
+                    .style(app.theme().pane_border_active),
+            ),
+            area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::SourceContext;
+    use crate::app::StackFrame;
+    use crate::dbgp::client::ContextGetResponse;
+    use crate::dbgp::client::Properties;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn frame(function: &str, line_no: u32, properties: Vec<Property>) -> StackFrame {
+        StackFrame {
+            level: 0,
+            source: SourceContext {
+                source: String::new(),
+                filename: "file:///app/order.php".to_string(),
+                line_no,
+            },
+            function: Some(function.to_string()),
+            contexts: HashMap::from([(
+                0,
+                ContextGetResponse {
+                    properties: Properties::from_properties(properties),
+                },
+            )]),
+        }
+    }
+
+    fn entry(stacks: Vec<StackFrame>) -> HistoryEntry {
+        HistoryEntry {
+            stacks,
+            eval: None,
+            watches: vec![],
+        }
+    }
+
+    #[test]
+    fn test_timeline() {
+        let order = |status: &str| {
+            let mut order = Property {
+                name: "order".to_string(),
+                fullname: "$order".to_string(),
+                ..Default::default()
+            };
+            order.children = Properties::from_properties(vec![Property {
+                name: "status".to_string(),
+                fullname: "$order->status".to_string(),
+                value: Some(status.to_string()),
+                ..Default::default()
+            }]);
+            order
+        };
+        let main = || frame("{main}", 30, vec![]);
+
+        let mut history = History::default();
+        history.entries = vec![
+            entry(vec![frame("checkout", 3, vec![order("new")]), main()]),
+            // called from checkout
+            entry(vec![frame("validate", 20, vec![order("other")]), frame("checkout", 4, vec![order("new")]), main()]),
+            // an unrelated function at the same depth
+            entry(vec![frame("refund", 12, vec![order("refunded")]), main()]),
+            entry(vec![frame("checkout", 5, vec![]), main()]),
+            entry(vec![frame("checkout", 6, vec![order("failed")]), main()]),
+        ];
+
+        let mut state = TimelineState::new("$order->status".to_string(), 0, &history.entries[0], 0).unwrap();
+        let rows = timeline(&history, &state);
+        assert_eq!(
+            vec![(0, 0, 3, "new"), (1, 1, 4, "new"), (4, 0, 6, "failed")],
+            rows.iter()
+                .map(|row| (row.step, row.stack_depth, row.line_no, row.property.value.as_deref().unwrap()))
+                .collect::<Vec<(usize, u16, u32, &str)>>()
+        );
+
+        state.context_id = 1;
+        assert_eq!(0, timeline(&history, &state).len());
+    }
+}
@@ -0,0 +1,169 @@
+use super::common::draw_input_dialog;
+use super::common::handle_input_dialog;
+use super::properties::draw_properties;
+use super::properties::PropertyTree;
+use super::View;
+use crate::app::App;
+use crate::app::EvalEntry;
+use crate::event::input::AppEvent;
+use crate::keymap::Action;
+use crate::keymap::Scope;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use tui_input::Input;
+
+pub struct WatchesComponent {}
+pub struct WatchDialog {}
+
+#[derive(Default)]
+pub struct WatchDialogState {
+    /// the watch being edited, or none when adding a watch
+    pub index: Option<usize>,
+    pub input: Input,
+}
+
+impl View for WatchesComponent {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        match event {
+            AppEvent::Scroll(amount) => Some(AppEvent::ScrollWatches(amount)),
+            AppEvent::Input(e) => match app.keymap().action(Scope::Watches, &e) {
+                Some(Action::WatchAdd) => Some(AppEvent::WatchAdd),
+                Some(Action::WatchEdit) => Some(AppEvent::WatchEdit),
+                Some(Action::WatchDelete) => Some(AppEvent::WatchDelete),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let selected = app.session_view.watches_scroll.0 as usize;
+        let evaluated = app.history_entry().map(|entry| &entry.watches);
+        let mut lines: Vec<Line> = Vec::new();
+        let mut selected_line = 0;
+
+        for (index, expr) in app.watches.iter().enumerate() {
+            if index == selected {
+                selected_line = lines.len();
+            }
+            lines.push(
+                Line::from(vec![Span::styled(expr.clone(), app.theme().syntax_label)]).style(
+                    match index == selected {
+                        true => app.theme().source_line_highlight,
+                        false => app.theme().source_line,
+                    },
+                ),
+            );
+            match watch_entry(evaluated, expr) {
+                Some(entry) => match &entry.response.error {
+                    Some(error) => lines.push(Line::from(vec![Span::styled(
+                        format!("  {}", error.message),
+                        app.theme().notification_error,
+                    )])),
+                    None => draw_properties(
+                        &app.theme(),
+                        entry.response.properties.defined_properties(),
+                        &mut lines,
+                        1,
+                        &mut PropertyTree::default(),
+                    ),
+                },
+                // the watch was added after this step
+                None => lines.push(Line::from(vec![Span::styled(
+                    "  not evaluated in this step",
+                    app.theme().source_annotation_historic,
+                )])),
+            }
+        }
+
+        let y_scroll = (selected_line as u16 + 1).saturating_sub(area.height);
+        frame.render_widget(
+            Paragraph::new(lines).scroll((y_scroll, app.session_view.watches_scroll.1)),
+            area,
+        );
+    }
+}
+
+impl View for WatchDialog {
+    fn handle(app: &mut App, event: AppEvent) -> Option<AppEvent> {
+        handle_input_dialog(
+            app,
+            event,
+            |app| &mut app.session_view.watch_dialog.input,
+            AppEvent::WatchDialogApply,
+            AppEvent::WatchDialogCancel,
+        )
+    }
+
+    fn draw(app: &App, frame: &mut Frame, area: Rect) {
+        let state = &app.session_view.watch_dialog;
+        let title = match state.index {
+            Some(_) => "Edit watch expression",
+            None => "Add watch expression",
+        };
+        draw_input_dialog(app, frame, area, title.to_string(), &state.input);
+    }
+}
+
+/// the evaluated watch for the expression
+fn watch_entry<'a>(evaluated: Option<&'a Vec<EvalEntry>>, expr: &str) -> Option<&'a EvalEntry> {
+    evaluated?.iter().find(|entry| entry.expr == expr)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::test::app;
+    use crate::app::test::connect;
+    use crate::app::test::entry;
+    use crate::config::Config;
+    use crate::dbgp::client::DbgpClient;
+    use crate::dbgp::client::DbgpError;
+    use crate::dbgp::client::EvalResponse;
+    use crate::dbgp::client::Properties;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_draw_watch_errors() {
+        let mut app = app(Config::default());
+        app.watches = vec!["$undefined".to_string(), "$added".to_string()];
+        let mut entry = entry(vec![]);
+        entry.watches = vec![EvalEntry {
+            expr: "$undefined".to_string(),
+            response: EvalResponse {
+                success: true,
+                error: Some(DbgpError {
+                    message: "error evaluating code".to_string(),
+                    code: "206".to_string(),
+                }),
+                properties: Properties::none(),
+            },
+        }];
+        connect(&mut app, DbgpClient::new(None), vec![entry]);
+
+        let mut terminal = Terminal::new(TestBackend::new(30, 4)).unwrap();
+        terminal
+            .draw(|frame| WatchesComponent::draw(&app, frame, frame.area()))
+            .unwrap();
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(30)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>().trim_end().to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "$undefined",
+                "  error evaluating code",
+                "$added",
+                "  not evaluated in this step",
+            ],
+            lines
+        );
+    }
+}
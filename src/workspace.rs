@@ -1,46 +1,205 @@
+use crate::dbgp::client::Breakpoint;
 use crate::dbgp::client::DbgpClient;
+use anyhow::Result;
+use serde::Deserialize;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 pub struct Document {
     pub filename: String,
     pub text: String,
+    /// set when the source was read from the local checkout rather than
+    /// fetched from the engine
+    pub local_path: Option<PathBuf>,
+}
+
+/// maps a path prefix on the debugged host (e.g. a container) to a path on
+/// this machine
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathMapping {
+    pub remote: String,
+    pub local: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathMappings {
+    mappings: Vec<PathMapping>,
+}
+
+impl PathMappings {
+    pub fn new(mappings: Vec<PathMapping>) -> Self {
+        Self { mappings }
+    }
+
+    /// translate a remote file URI to a local file URI
+    pub fn to_local(&self, fileuri: &str) -> Option<String> {
+        self.mappings
+            .iter()
+            .filter_map(|m| replace_prefix(fileuri, &m.remote, &m.local).map(|uri| (m.remote.len(), uri)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, uri)| uri)
+    }
+
+    /// translate a local file URI to a remote file URI
+    pub fn to_remote(&self, fileuri: &str) -> Option<String> {
+        self.mappings
+            .iter()
+            .filter_map(|m| replace_prefix(fileuri, &m.local, &m.remote).map(|uri| (m.local.len(), uri)))
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, uri)| uri)
+    }
+
+    pub fn local_uri(&self, fileuri: &str) -> String {
+        self.to_local(fileuri).unwrap_or(fileuri.to_string())
+    }
+
+    pub fn remote_uri(&self, fileuri: &str) -> String {
+        self.to_remote(fileuri).unwrap_or(fileuri.to_string())
+    }
+
+    /// the breakpoint as it should be set on the engine
+    pub fn remote_breakpoint(&self, breakpoint: &Breakpoint) -> Breakpoint {
+        let mut breakpoint = breakpoint.clone();
+        breakpoint.filename = breakpoint.filename.map(|f| self.remote_uri(&f));
+        breakpoint
+    }
+}
+
+fn uri_path(fileuri: &str) -> &str {
+    fileuri.strip_prefix("file://").unwrap_or(fileuri)
+}
+
+fn replace_prefix(fileuri: &str, from: &str, to: &str) -> Option<String> {
+    let rest = uri_path(fileuri).strip_prefix(from.trim_end_matches('/'))?;
+    // only match whole path segments
+    if !rest.is_empty() && !rest.starts_with('/') {
+        return None;
+    }
+    Some(format!("file://{}{}", to.trim_end_matches('/'), rest))
 }
 
 pub struct Workspace {
     client: Arc<Mutex<DbgpClient>>,
     documents: HashMap<String, Document>,
+    pub path_mappings: PathMappings,
 }
 
 impl Workspace {
-    pub fn new(client: Arc<Mutex<DbgpClient>>) -> Self {
+    pub fn new(client: Arc<Mutex<DbgpClient>>, path_mappings: PathMappings) -> Self {
         Workspace {
             client,
             documents: HashMap::new(),
+            path_mappings,
         }
     }
 
-    pub async fn open(&mut self, filename: String) -> &Document {
+    pub async fn open(&mut self, filename: String) -> Result<&Document> {
         let entry = self.documents.entry(filename.clone());
         if let Entry::Vacant(entry) = entry {
-            let source = self.client
-                .lock()
-                .await
-                .source(filename.to_string())
-                .await
-                .expect("Could not retrieve source");
-            entry.insert(Document {
-                filename: filename.clone(),
-                text: source.clone(),
-            });
+            // prefer the local checkout and fall back to the engine if the
+            // file does not exist locally
+            let local_path = self
+                .path_mappings
+                .to_local(&filename)
+                .map(|uri| PathBuf::from(uri_path(&uri)))
+                .filter(|path| path.is_file());
+
+            let document = match local_path {
+                Some(path) => Document {
+                    filename: filename.clone(),
+                    text: tokio::fs::read_to_string(&path).await?,
+                    local_path: Some(path),
+                },
+                None => Document {
+                    filename: filename.clone(),
+                    text: self
+                        .client
+                        .lock()
+                        .await
+                        .source(filename.to_string())
+                        .await?,
+                    local_path: None,
+                },
+            };
+            entry.insert(document);
         };
 
-        self.documents.get(filename.as_str()).unwrap()
+        Ok(self.documents.get(filename.as_str()).unwrap())
+    }
+
+    pub fn document(&self, filename: &str) -> Option<&Document> {
+        self.documents.get(filename)
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn mappings() -> PathMappings {
+        PathMappings::new(vec![
+            PathMapping {
+                remote: "/var/www/html".to_string(),
+                local: "/home/me/app".to_string(),
+            },
+            PathMapping {
+                remote: "/var/www/html/vendor/".to_string(),
+                local: "/home/me/vendor".to_string(),
+            },
+        ])
+    }
+
+    #[test]
+    fn test_path_mappings() {
+        let mappings = mappings();
+        assert_eq!(
+            Some("file:///home/me/app/src/Foo.php".to_string()),
+            mappings.to_local("file:///var/www/html/src/Foo.php")
+        );
+        assert_eq!(
+            Some("file:///home/me/vendor/foo/Bar.php".to_string()),
+            mappings.to_local("file:///var/www/html/vendor/foo/Bar.php")
+        );
+        assert_eq!(None, mappings.to_local("file:///var/www/html2/Foo.php"));
+        assert_eq!(
+            "file:///var/www/html/src/Foo.php",
+            mappings.remote_uri("file:///home/me/app/src/Foo.php")
+        );
+        assert_eq!("file:///tmp/Foo.php", mappings.remote_uri("file:///tmp/Foo.php"));
+
+        let breakpoint = mappings.remote_breakpoint(&Breakpoint::line(
+            "file:///home/me/vendor/foo/Bar.php".to_string(),
+            10,
+        ));
+        assert_eq!(
+            Some("file:///var/www/html/vendor/foo/Bar.php".to_string()),
+            breakpoint.filename
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_reads_local_file() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("debug-tui-workspace-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src"))?;
+        std::fs::write(dir.join("src/Foo.php"), "<?php echo 'local';")?;
+
+        let mut workspace = Workspace::new(
+            Arc::new(Mutex::new(DbgpClient::new(None))),
+            PathMappings::new(vec![PathMapping {
+                remote: "/var/www/html".to_string(),
+                local: dir.to_string_lossy().to_string(),
+            }]),
+        );
+        let document = workspace.open("file:///var/www/html/src/Foo.php".to_string()).await?;
+        assert_eq!("<?php echo 'local';", document.text);
+        assert_eq!(Some(dir.join("src/Foo.php")), document.local_path);
 
-    pub(crate) fn reset(&mut self) {
-        self.documents = HashMap::new();
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
     }
 }